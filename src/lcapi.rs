@@ -40,7 +40,7 @@ pub async fn fetch_recently_submitted(username: &str) -> Result<Vec<Submission>>
                 let problem = Problem {
                     title: val.get("title")?.as_str()?.to_string(),
                     url: val.get("titleSlug")?.as_str()?.to_string(),
-                    difficulty: String::from("NULL"),
+                    difficulty: String::from(UNKNOWN_DIFFICULTY),
                 };
                 let sub = Submission {
                     username: username.to_string(),
@@ -126,10 +126,73 @@ pub async fn fetch_user(username: &str) -> Result<User> {
     })
 }
 
-/// Runs a GraphQL query on the leetcode servers for `username`.
+/// Fetches the "About me" section of `username`'s LeetCode profile (empty if they have none).
+pub async fn fetch_about_me(username: &str) -> Result<String> {
+    let response = run_user_query("queries/lcuser_publicprofile.graphql", username).await?;
+    let data = response.data.context("No data found in the response.")?;
+
+    let about_me = data
+        .get("matchedUser")
+        .filter(|user| !user.is_null())
+        .with_context(|| format!("Leetcode user {} does not exist.", username))?
+        .get("profile")
+        .context(err_cant_get("profile", username))?
+        .get("aboutMe")
+        .context(err_cant_get("about me", username))?
+        .as_str()
+        .unwrap_or_default();
+
+    Ok(about_me.to_owned())
+}
+
+/// Fetches the URL of `username`'s LeetCode avatar, if they have one.
+pub async fn fetch_avatar(username: &str) -> Result<Option<String>> {
+    let response = run_user_query("queries/lcuser_publicprofile.graphql", username).await?;
+    let data = response.data.context("No data found in the response.")?;
+
+    let avatar = data
+        .get("matchedUser")
+        .filter(|user| !user.is_null())
+        .with_context(|| format!("Leetcode user {} does not exist.", username))?
+        .get("profile")
+        .context(err_cant_get("profile", username))?
+        .get("userAvatar")
+        .and_then(Value::as_str)
+        .filter(|avatar| !avatar.is_empty());
+
+    Ok(avatar.map(str::to_owned))
+}
+
+/// Fetches the difficulty (`Easy`, `Medium` or `Hard`) of the problem with the slug `slug`.
+pub async fn fetch_problem_difficulty(slug: &str) -> Result<String> {
+    let variables = serde_json::json!({ "titleSlug": slug });
+    let response = run_query("queries/lcproblem_difficulty.graphql", variables).await?;
+    let data = response.data.context("No data found in the response.")?;
+
+    let difficulty = data
+        .get("question")
+        .filter(|question| !question.is_null())
+        .with_context(|| format!("Leetcode problem {slug} does not exist."))?
+        .get("difficulty")
+        .and_then(Value::as_str)
+        .with_context(|| format!("Couldn't get difficulty for {slug}"))?;
+
+    Ok(difficulty.to_owned())
+}
+
+/// Runs the main GraphQL user query on the leetcode servers for `username`.
 async fn query_user(username: &str) -> Result<QueryResponse> {
-    let query = read_query_from_file("queries/lcuser.graphql")?;
-    let variables = serde_json::json!({ "username": username });
+    run_user_query("queries/lcuser.graphql", username).await
+}
+
+/// Runs the GraphQL query stored at `path` on the leetcode servers for `username`.
+async fn run_user_query(path: &str, username: &str) -> Result<QueryResponse> {
+    run_query(path, serde_json::json!({ "username": username })).await
+}
+
+/// Runs the GraphQL query stored at `path` on the leetcode servers.
+async fn run_query(path: &str, variables: Value) -> Result<QueryResponse> {
+    let query = read_query_from_file(path)?;
     let body = RequestBody { query, variables };
     let headers = HeaderMap::from_iter([
         (
@@ -1,9 +1,12 @@
 use serenity::async_trait;
-use serenity::model::channel::Message;
+use serenity::builder::{CreateEmbed, CreateEmbedAuthor, CreateMessage};
+use serenity::model::application::{Command, Interaction};
+use serenity::model::channel::{Message, Reaction};
 use serenity::model::gateway::Ready;
+use serenity::model::id::{ChannelId, MessageId};
 use serenity::prelude::*;
 
-use chrono::{Timelike, Utc};
+use chrono::{DateTime, DurationRound, TimeDelta, Timelike, Utc};
 
 use std::time::Duration as StdDuration;
 use tokio::time::{Duration, sleep};
@@ -11,20 +14,50 @@ use tokio::time::{Duration, sleep};
 use dotenv::dotenv;
 
 use crate::lcapi;
-use crate::lcdb;
-use crate::models;
+use crate::lcdb::Store;
+use crate::models::{self, AnnouncementKind, Render};
 
 mod commands;
 use commands::Commands;
 
+mod challenge;
+mod compare;
+mod digest;
+mod goal;
+mod kudos;
+mod leaderboard;
+mod lists;
+mod recap;
+mod reminder;
+mod season;
+
+mod response;
+use response::Response;
+
 use anyhow::{Context, Result};
 
-struct LeekHandler;
+use std::sync::Arc;
+
+struct LeekHandler {
+    store: Arc<dyn Store>,
+}
 #[async_trait]
 impl EventHandler for LeekHandler {
     async fn ready(&self, ctx: serenity::client::Context, _ready: Ready) {
         log::info!("Bot is connected and ready!");
-        let channel_id = getenv_announcements_channel();
+
+        // Every command can also be used as a slash command.
+        if let Err(err) = Command::set_global_commands(&ctx.http, commands::slash_commands()).await {
+            log::error!("Couldn't register slash commands: {err}");
+        }
+
+        // Before guilds, the bot announced to a single channel set in the environment.
+        // If that's still set, hand everything from back then over to that channel's guild.
+        if let Some(channel_id) = getenv_legacy_announcements_channel()
+            && let Err(err) = claim_legacy_channel(&ctx, self.store.as_ref(), channel_id).await
+        {
+            log::error!("Couldn't claim legacy announcements channel {channel_id}: {err}");
+        }
 
         // Display most recent commit on restart.
         if !commands::is_debug_mode() {
@@ -32,26 +65,48 @@ impl EventHandler for LeekHandler {
                 .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
                 .unwrap_or(String::from("no commit message"));
 
-            serenity::model::id::ChannelId::new(channel_id)
-                .say(&ctx.http, format!("LeekBot 2.0 updated: ```\n{}\n```", commit_msg.trim()))
-                .await
-                .map_or_else(|err| log::error!("Couldn't send welcome message: {err}"), |_|{});
+            let msg = format!("LeekBot 2.0 updated: ```\n{}\n```", commit_msg.trim());
+            for guild_id in self.store.query_guilds().unwrap_or_default() {
+                announce(&ctx, self.store.as_ref(), guild_id, AnnouncementKind::Updates, &msg)
+                    .await
+                    .map_or_else(|err| log::error!("Couldn't send welcome message: {err}"), |_|{});
+            }
         }
 
         let daily_checker_ctx = ctx.clone();
+        let daily_checker_store = self.store.clone();
         tokio::spawn(async move {
             loop {
                 sleep_until_midnight_utc().await;
-                if let Err(err) = streak_handler(&daily_checker_ctx, channel_id).await {
+                let store = daily_checker_store.as_ref();
+                if let Err(err) = streak_handler(&daily_checker_ctx, store).await {
                     log::error!("Error sending scheduled message: {}", err);
                 }
-                if let Err(err) = lcdb::clean_cache() {
+                if let Err(err) = season::roll_over(&daily_checker_ctx, store, Utc::now()).await {
+                    log::error!("Error rolling over seasons: {}", err);
+                }
+                if let Err(err) = store.clean_cache() {
                     log::error!("Error clearing recent cache: {}", err);
                 }
             }
         });
 
+        let recap_ctx = ctx.clone();
+        let recap_store = self.store.clone();
+        tokio::spawn(async move {
+            loop {
+                let hour = sleep_until_next_hour().await;
+                if let Err(err) = recap::post_due(&recap_ctx, recap_store.as_ref(), hour).await {
+                    log::error!("Error posting recaps: {err}");
+                }
+                if let Err(err) = reminder::send_due(&recap_ctx, recap_store.as_ref(), hour).await {
+                    log::error!("Error sending streak reminders: {err}");
+                }
+            }
+        });
+
         let recent_checker_ctx = ctx.clone();
+        let recent_checker_store = self.store.clone();
         tokio::spawn(async move {
             const RECENT_TIME_INTERVAL_SECS: u64 = 30; // 30 second cooldown between checks
             let mut interval =
@@ -59,16 +114,22 @@ impl EventHandler for LeekHandler {
             loop {
                 interval.tick().await;
 
-                match check_recent_submissions().await {
+                let store = recent_checker_store.as_ref();
+                match check_recent_submissions(store).await {
                     Ok(new_submissions) => {
                         for submission in new_submissions {
-                            announce_submission(&submission, &recent_checker_ctx, channel_id).await;
+                            announce_submission(&submission, &recent_checker_ctx, store).await;
                         }
                     }
                     Err(err) => {
                         log::error!("Error checking recent submissions: {}", err);
                     }
                 }
+
+                let now = Utc::now().timestamp_millis().max(0) as usize;
+                if let Err(err) = challenge::expire_due(&recent_checker_ctx, store, now).await {
+                    log::error!("Error expiring challenges: {err}");
+                }
             }
         });
     }
@@ -88,54 +149,184 @@ impl EventHandler for LeekHandler {
         }
 
         // Commands
-        if content.starts_with(commands::getenv_call_token()) && content.len() > 1 {
-            let response = match Commands::run_command(&ctx, &msg).await {
-                Ok(message) => message,
-                Err(err) => {
-                    log::error!("{err}");
-                    format!("Error: {err}")
-                }
-            };
-
-            // Discord doesn't like sending empty messages.
-            // If everything is ok and the bot doesn't have anything to say, return early.
-            if response.is_empty() {
-                return;
+        let response = match Commands::run_command(&ctx, &msg, self.store.as_ref()).await {
+            Ok(message) => message,
+            Err(err) => {
+                log::error!("{err}");
+                format!("Error: {err}")
             }
+        };
 
-            // Attempt to send response.
-            // If something goes wrong, we want to let the user know, if possible,
-            //   so we try to send another "Oops, internal error" before exiting.
-            // If *that* message can't be sent, it can't be helped...
-            //   but it will be logged on our end anyways.
-            if let Err(why) = channel.say(&ctx.http, response).await {
-                let _ = channel.say(&ctx.http, "Oops, internal error.").await;
-                log::error!("Error sending message: {why:?}");
+        // Discord doesn't like sending empty messages.
+        // If everything is ok and the bot doesn't have anything to say, return early.
+        let response = Response::new(&response);
+        if response.is_empty() {
+            return;
+        }
+
+        // Attempt to send response.
+        // If something goes wrong, we want to let the user know, if possible,
+        //   so we try to send another "Oops, internal error" before exiting.
+        // If *that* message can't be sent, it can't be helped...
+        //   but it will be logged on our end anyways.
+        // Long responses are sent a page at a time.
+        let (content, components) = response.first_page();
+        let message = CreateMessage::new().content(content).components(components);
+        if let Err(why) = channel.send_message(&ctx.http, message).await {
+            let _ = channel.say(&ctx.http, "Oops, internal error.").await;
+            log::error!("Error sending message: {why:?}");
+        }
+    }
+    async fn interaction_create(&self, ctx: serenity::client::Context, interaction: Interaction) {
+        let store = self.store.as_ref();
+        match interaction {
+            Interaction::Command(command) => {
+                if let Err(err) = Commands::run_slash_command(&ctx, &command, store).await {
+                    log::error!("Error running slash command {}: {err}", command.data.name);
+                }
+            }
+            Interaction::Autocomplete(command) => {
+                if let Err(err) = Commands::autocomplete(&ctx, &command, store).await {
+                    log::error!("Error autocompleting {}: {err}", command.data.name);
+                }
             }
+            Interaction::Component(component) => {
+                let result = match response::is_page_button(&component.data.custom_id) {
+                    true => response::handle_page_button(&ctx, &component).await,
+                    false => Commands::handle_component(&ctx, &component, store).await,
+                };
+
+                if let Err(err) = result {
+                    log::error!("Error handling component {}: {err}", component.data.custom_id);
+                }
+            }
+            _ => {}
+        }
+    }
+    async fn reaction_add(&self, _ctx: serenity::client::Context, reaction: Reaction) {
+        let now = Utc::now().timestamp_millis().max(0) as usize;
+        if let Err(err) = kudos::give(self.store.as_ref(), &reaction, now) {
+            log::error!("Error counting reaction to message {}: {err}", reaction.message_id);
+        }
+    }
+    async fn reaction_remove(&self, _ctx: serenity::client::Context, reaction: Reaction) {
+        if let Err(err) = kudos::take_back(self.store.as_ref(), &reaction) {
+            log::error!("Error uncounting reaction to message {}: {err}", reaction.message_id);
         }
     }
 }
 
-/// Get the announcements channel ID. May panic.
-fn getenv_announcements_channel() -> u64 {
-    std::env::var("ANNOUNCEMENTS_CHANNEL_ID")
-        .expect(".env file does not contain 'ANNOUNCEMENTS_CHANNEL_ID.")
+/// Get the announcements channel ID from before guilds were supported, if it is still set.
+fn getenv_legacy_announcements_channel() -> Option<u64> {
+    let channel_id = std::env::var("ANNOUNCEMENTS_CHANNEL_ID").ok()?;
+    channel_id
         .parse()
-        .expect("'ANNOUNCEMENTS_CHANNEL_ID should be parseable into a u64.")
+        .inspect_err(|_| log::error!("'ANNOUNCEMENTS_CHANNEL_ID' should be parseable into a u64."))
+        .ok()
+}
+
+/// Makes `channel_id` the announcements channel of the guild it belongs to (unless that guild
+/// already has one), and moves any pre-guild user preferences over to that guild.
+async fn claim_legacy_channel(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    channel_id: u64) -> Result<()>
+{
+    let channel = ChannelId::new(channel_id)
+        .to_channel(&ctx.http)
+        .await?
+        .guild()
+        .context("Legacy announcements channel is not in a guild.")?;
+    let guild_id = channel.guild_id.get();
+
+    let mut settings = store.query_guild_settings(guild_id)?;
+    if settings.announcements_channel.is_none() {
+        settings.announcements_channel = Some(channel_id);
+        store.update_guild_settings(guild_id, &settings)?;
+    }
+
+    let claimed = store.claim_legacy_preferences(guild_id)?;
+    if claimed > 0 {
+        log::info!("Moved {claimed} legacy users into guild {guild_id}.");
+    }
+
+    Ok(())
+}
+
+/// Sends `msg` to the channel `guild_id` posts announcements of `kind` to, if it has one.
+async fn announce(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    guild_id: u64,
+    kind: AnnouncementKind,
+    msg: &str) -> Result<()>
+{
+    let Some(channel_id) = store.query_guild_settings(guild_id)?.channel(kind) else {
+        log::debug!("[announce] Guild {guild_id} has no channel for {} announcements, skipping.",
+                    kind.name());
+        return Ok(());
+    };
+
+    ChannelId::new(channel_id)
+        .say(&ctx.http, msg)
+        .await?;
+
+    Ok(())
+}
+
+/// Sends `embed` to the channel `guild_id` posts announcements of `kind` to, if it has one.
+/// Falls back to `text` if the embed can't be sent (e.g. the bot may not embed links there).
+/// Returns the ID of the message that was sent, if any.
+async fn announce_embed(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    guild_id: u64,
+    kind: AnnouncementKind,
+    embed: CreateEmbed,
+    text: &str) -> Result<Option<MessageId>>
+{
+    let Some(channel_id) = store.query_guild_settings(guild_id)?.channel(kind) else {
+        log::debug!("[announce_embed] Guild {guild_id} has no channel for {} announcements, \
+                     skipping.", kind.name());
+        return Ok(None);
+    };
+
+    let (message, _) = send_embed(ctx, ChannelId::new(channel_id), embed, text).await?;
+
+    Ok(Some(message.id))
+}
+
+/// Sends `embed` to `channel`, or `text` if the embed can't be sent.
+/// Returns the message that was sent, and whether it's the embed.
+async fn send_embed(
+    ctx: &serenity::client::Context,
+    channel: ChannelId,
+    embed: CreateEmbed,
+    text: &str) -> Result<(Message, bool)>
+{
+    match channel.send_message(&ctx.http, CreateMessage::new().embed(embed)).await {
+        Ok(message) => Ok((message, true)),
+        Err(err) => {
+            log::warn!("[send_embed] Couldn't send embed to channel {channel}, sending text \
+                        instead: {err}");
+            Ok((channel.say(&ctx.http, text).await?, false))
+        }
+    }
 }
 
-pub async fn run_leekbot() -> Result<()> {
+pub async fn run_leekbot(store: Arc<dyn Store>) -> Result<()> {
     // Load discord bot token
     dotenv().ok();
     let token = std::env::var("DISCORD_TOKEN")
         .context("Expected 'DISCORD_TOKEN=<token>' in .env in project root.")?;
 
     let intents = GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
 
     let mut client = Client::builder(&token, intents)
-        .event_handler(LeekHandler)
+        .event_handler(LeekHandler { store })
         .await
         .context("Error creating client.")?;
 
@@ -148,15 +339,15 @@ pub async fn run_leekbot() -> Result<()> {
 /// the recency cache, and returns the ones that may be announced.
 ///
 /// Intended to be run regularly.
-async fn check_recent_submissions() -> Result<Vec<models::Submission>> {
+async fn check_recent_submissions(store: &dyn Store) -> Result<Vec<models::Submission>> {
     let mut result = Vec::new();
 
-    let users = lcdb::query_tracked_users()?;
+    let users = store.query_tracked_users()?;
 
-    update_db_from_leetcode(&users).await?;
+    update_db_from_leetcode(store, &users).await?;
 
     for user in users {
-        match lcdb::query_uncached_submissions(&user) {
+        match store.query_uncached_submissions(&user) {
             Ok(subs) => result.extend(subs),
             Err(err) => log::error!("[check_recent_submissions] Error querying database for \
                                     uncached submissions for {}: {}", 
@@ -169,17 +360,19 @@ async fn check_recent_submissions() -> Result<Vec<models::Submission>> {
 
 /// Reaches out to LeetCode and sees if any of our tracked users have any new submissions:
 /// if they have any, updates the Submissions table of the database.
-async fn update_db_from_leetcode(users: &[models::User]) -> Result<()> {
+async fn update_db_from_leetcode(store: &dyn Store, users: &[models::User]) -> Result<()> {
     for user in users {
         match lcapi::fetch_recently_submitted(&user.username).await {
             Ok(recent_subs) => {
-                for submission in recent_subs {
-                    if let Err(err) = lcdb::insert_problem(&submission.problem) {
+                for mut submission in recent_subs {
+                    fill_difficulty(store, &mut submission.problem).await;
+
+                    if let Err(err) = store.insert_problem(&submission.problem) {
                         log::warn!("[update_db_from_leetcode] Could not insert problem: {}: {err}", 
                                     submission.problem.title);
                     }
 
-                    if let Err(err) = lcdb::insert_submission(&submission) {
+                    if let Err(err) = store.insert_submission(&submission) {
                         log::warn!("[update_db_from_leetcode] Could not insert submission: \
                                     {submission}: {err}");
                     }
@@ -195,29 +388,72 @@ async fn update_db_from_leetcode(users: &[models::User]) -> Result<()> {
     Ok(())
 }
 
+/// Fills in the difficulty of a problem fresh from LeetCode's recent submissions (which don't
+/// have it), from Problems if it's known there, otherwise by looking it up.
+async fn fill_difficulty(store: &dyn Store, problem: &mut models::Problem) {
+    let known = store
+        .query_problem(&problem.title)
+        .ok()
+        .flatten()
+        .filter(models::Problem::is_difficulty_known);
+
+    if let Some(known) = known {
+        problem.difficulty = known.difficulty;
+        return;
+    }
+
+    match lcapi::fetch_problem_difficulty(&problem.url).await {
+        Ok(difficulty) => {
+            problem.difficulty = difficulty;
+            // In case it was stored back when difficulties weren't looked up.
+            if let Err(err) = store.update_problem_difficulty(&problem.title, &problem.difficulty) {
+                log::warn!("[fill_difficulty] Could not update difficulty of {}: {err}",
+                           problem.title);
+            }
+        }
+        Err(err) => log::warn!("[fill_difficulty] Could not fetch difficulty of {}: {err}",
+                               problem.title),
+    }
+}
+
 /// Handles streaks by checking if tracked users have submitted a problem recently.
-async fn streak_handler(ctx: &serenity::client::Context, channel_id: u64) -> Result<()> {
-    let channel = serenity::model::id::ChannelId::new(channel_id);
-    for user in lcdb::query_tracked_users()? {
-        let active = lcdb::is_active(&user)?;
-        let streak = lcdb::query_streak(&user)?;
-        if active {
-            lcdb::streak_increment(&user)?;
-            channel
-                .say(
-                    &ctx.http,
-                    format!(
-                        "{} is on a roll with a streak of {}!",
-                        &user.username,
-                        streak + 1
-                    ),
-                )
-                .await?;
+/// Streak freezes are earned along the way, and spent on missed days instead of the streak.
+///
+/// Streaks are global, but they're reported to every guild tracking the user.
+async fn streak_handler(ctx: &serenity::client::Context, store: &dyn Store) -> Result<()> {
+    for user in store.query_tracked_users()? {
+        let active = store.is_active(&user)?;
+        let streak = store.query_streak(&user)?;
+        let freezes = store.query_streak_freezes(&user)?;
+        let msg = if active {
+            store.streak_increment(&user)?;
+            let streak = streak + 1;
+            let mut msg = format!("{} is on a roll with a streak of {streak}!", &user.username);
+            let earned = streak % models::STREAK_FREEZE_DAYS == 0;
+            if earned && freezes < models::MAX_STREAK_FREEZES {
+                store.update_streak_freezes(&user, freezes + 1)?;
+                msg += &format!(" They earned a streak freeze ({}/{}).",
+                                freezes + 1, models::MAX_STREAK_FREEZES);
+            }
+            msg
+        } else if streak > 0 && freezes > 0 {
+            store.update_streak_freezes(&user, freezes - 1)?;
+            format!("{} missed a day, but a streak freeze saved their streak of {streak}! \
+                     ({} left)", &user.username, freezes - 1)
         } else if streak > 0 {
-            lcdb::streak_break(&user)?;
-            channel
-                .say(&ctx.http, format!("{} lost their streak!", &user.username))
-                .await?;
+            store.streak_break(&user)?;
+            format!("{} lost their streak!", &user.username)
+        } else {
+            continue;
+        };
+
+        // A guild we can't announce to mustn't hold up everyone else's streaks.
+        for (guild_id, _) in store.query_tracking_guilds(&user)? {
+            let result = announce(ctx, store, guild_id, AnnouncementKind::Streaks, &msg).await;
+            if let Err(err) = result {
+                log::error!("Couldn't announce {}'s streak to guild {guild_id}: {err}",
+                            user.username);
+            }
         }
     }
 
@@ -244,13 +480,26 @@ async fn sleep_until_midnight_utc() {
     sleep(sleep_duration).await;
 }
 
-/// Potentially announces a submission and adds it to the RecentCache.
+/// Sleeps until the top of the next hour (UTC), and returns it.
+async fn sleep_until_next_hour() -> DateTime<Utc> {
+    let now = Utc::now();
+    let next_hour = now
+        .duration_trunc(TimeDelta::hours(1))
+        .unwrap_or(now)
+        + TimeDelta::hours(1);
+
+    sleep((next_hour - now).to_std().unwrap_or_default()).await;
+    next_hour
+}
+
+/// Potentially announces a submission in every guild tracking its user, and adds it to the
+/// RecentCache.
 ///
 /// Note that some users may not want their submissions announced; we reflect that here.
 async fn announce_submission(
     submission: &models::Submission,
     ctx: &serenity::client::Context,
-    channel_id: u64) 
+    store: &dyn Store)
 {
     log::trace!("[announce_submission] Updating RecentCache...");
 
@@ -258,20 +507,28 @@ async fn announce_submission(
     let problem = &submission.problem.title;
 
     // Get the User object
-    let Ok(Some(user)) = lcdb::query_user(&submission.username) else {
+    let Ok(Some(user)) = store.query_user(&submission.username) else {
         log::error!("[announce_submission] Attempted to announce submission for {}, but
                      couldn't find the user in the database.", submission.username);
         return;
     };
 
-    // Get the UserPreferences object for this user
-    let Ok(Some(prefs)) = lcdb::query_user_preferences(&user) else {
-        log::error!("[announce_submission] Attempted to gather preferences for {}, but
+    // Get the guilds tracking this user, along with the user's preferences in each of them
+    let Ok(guilds) = store.query_tracking_guilds(&user) else {
+        log::error!("[announce_submission] Attempted to gather tracking guilds for {}, but
                      couldn't find them in the database.", user.username);
         return;
     };
 
-    match lcdb::insert_cache_submission(submission) {
+    // Announcements show the user's LeetCode avatar, if we can get it.
+    let avatar = lcapi::fetch_avatar(username)
+        .await
+        .inspect_err(|err| log::warn!("[announce_submission] Couldn't fetch {username}'s avatar: \
+                                       {err}"))
+        .ok()
+        .flatten();
+
+    match store.insert_cache_submission(submission) {
         Ok(true) => log::debug!("[announce_submission] Added {username}'s submission '{problem}' \
                                  to recent cache."),
 
@@ -282,57 +539,156 @@ async fn announce_submission(
         Err(err) => log::error!("[announce_submission] Couldn't insert cache submission: {err}"),
     }
 
-    if prefs.announcement.is_some() {
+    for &(guild_id, prefs) in &guilds {
+        if prefs.announcement.is_none() {
+            log::info!("{username} submitted a new problem '{problem}', but prefers to move in \
+                        silence in guild {guild_id}.");
+            continue;
+        }
+
+        // Solves of problems on lists the user follows come with their progress on them.
+        let notes = match submission.accepted {
+            true => lists::progress_notes(store, guild_id, submission)
+                .inspect_err(|err| log::warn!("[announce_submission] Couldn't get {username}'s \
+                                               list progress: {err}"))
+                .unwrap_or_default(),
+            false => Vec::new(),
+        };
 
-        let Some(msg) = submission_announcement(submission, prefs) else {
+        let Some((embed, text)) =
+            submission_announcement(submission, prefs, avatar.as_deref(), &notes)
+        else {
             log::info!("{username} has a new submission for {problem}, but they don't want to \
-                        have it announced (likely due to failure).");
-            return;
+                        have it announced in guild {guild_id} (likely due to failure).");
+            continue;
         };
 
-        log::info!("Sending message for {username}'s new submission: {problem}");
-        if let Err(err) = serenity::model::id::ChannelId::new(channel_id)
-            .say(&ctx.http, msg)
-            .await
-        {
-            log::error!("Error sending scheduled message: {}", err);
+        log::info!("Sending message for {username}'s new submission to guild {guild_id}: {problem}");
+        let kind = match submission.accepted {
+            true => AnnouncementKind::Accepted,
+            false => AnnouncementKind::Failures,
+        };
+
+        let result = match digest_target(store, guild_id, kind, prefs) {
+            Ok(Some((channel, window))) => {
+                let has_link = prefs.announcement.is_some_and(|a| a.has_submission_link);
+                let entry = digest::Entry::new(submission, has_link, &notes);
+                digest::announce(ctx, channel, window, entry, avatar.as_deref(), embed, &text)
+                    .await
+                    .map(Some)
+            }
+            Ok(None) => announce_embed(ctx, store, guild_id, kind, embed, &text).await,
+            Err(err) => Err(err),
+        };
+
+        // Reactions to the announcement count as kudos for its user.
+        match result {
+            Ok(Some(message_id)) => {
+                let announcement = models::SubmissionAnnouncement {
+                    message_id: message_id.get(),
+                    guild_id,
+                    username: username.clone(),
+                };
+                if let Err(err) = store.insert_submission_announcement(&announcement) {
+                    log::error!("Couldn't record announcement of {username}'s submission: {err}");
+                }
+            }
+            Ok(None) => {}
+            Err(err) => log::error!("Error sending scheduled message: {}", err),
+        }
+    }
+
+    // Solves move pinned leaderboards and group goals, and may win challenges, whether or not
+    // they were announced.
+    if submission.accepted {
+        let now = Utc::now();
+        for (guild_id, _) in guilds {
+            let now_millis = now.timestamp_millis().max(0) as usize;
+            if let Err(err) = leaderboard::refresh_pinned(ctx, store, guild_id, now_millis).await {
+                log::error!("Couldn't refresh pinned leaderboard of guild {guild_id}: {err}");
+            }
+            if let Err(err) = goal::announce_milestone(ctx, store, guild_id, now).await {
+                log::error!("Couldn't announce group goal milestone of guild {guild_id}: {err}");
+            }
+        }
+
+        if let Err(err) = challenge::resolve(ctx, store, submission).await {
+            log::error!("Couldn't resolve challenges won by {username}: {err}");
         }
-    } else {
-        log::info!("{user} submitted a new problem '{problem}', but prefers to move in silence.")
     }
 }
 
-/// Creates a submission announcement String from a Submission.
+/// Where a user's announcements of `kind` are digested in `guild_id`, and over how long.
+/// None if they aren't (or aren't announced at all).
+fn digest_target(
+    store: &dyn Store,
+    guild_id: u64,
+    kind: AnnouncementKind,
+    prefs: models::UserPreferences) -> Result<Option<(ChannelId, StdDuration)>>
+{
+    let settings = store.query_guild_settings(guild_id)?;
+    let (Some(channel_id), Some(announcement)) = (settings.channel(kind), prefs.announcement)
+    else {
+        return Ok(None);
+    };
+
+    Ok(settings
+        .digest_window(&announcement)
+        .map(|window| (ChannelId::new(channel_id), window)))
+}
+
+/// Creates a submission announcement from a Submission: as an embed, and as plain text for
+/// wherever the embed can't be sent. `notes` are mentioned alongside, e.g. "12/75 on blind75".
 fn submission_announcement(
     submission: &models::Submission,
-    prefs: models::UserPreferences
-) -> Option<String>
+    prefs: models::UserPreferences,
+    avatar: Option<&str>,
+    notes: &[String],
+) -> Option<(CreateEmbed, String)>
 {
     let has_link = prefs.announcement?.has_submission_link;
     let announce_failures = prefs.announcement?.announce_failures;
 
-    if submission.accepted {
-        let mut msg = format!(
+    let (headline, mut text) = if submission.accepted {
+        let text = format!(
             "✅ {} just completed [{}]({})!",
-            submission.username, submission.problem.title, submission.problem.url);
+            submission.username, submission.problem.title, submission.problem.link());
+
+        (format!("{} just completed it!", submission.username), text)
+    } else if announce_failures {
+        let misattempt = generate_misattempt_msg();
+        let text = format!(
+            "❌ {} just submitted an attempt for [{}]({}), but {}",
+            submission.username,
+            submission.problem.title,
+            submission.problem.link(),
+            misattempt);
+
+        (format!("{} just submitted an attempt, but {misattempt}", submission.username), text)
+    } else {
+        return None;
+    };
 
-        if has_link {
-            msg += &format!("\n\t{}", submission.url);
-        }
+    let mut author = CreateEmbedAuthor::new(headline)
+        .url(format!("https://leetcode.com/u/{}/", submission.username));
+    if let Some(avatar) = avatar {
+        author = author.icon_url(avatar);
+    }
 
-        Some(msg)
-    } else {
-        announce_failures.then(|| {
-            format!(
-                "❌ {} just submitted an attempt for [{}]({}), but {}\n\t{}",
-                submission.username,
-                submission.problem.title,
-                submission.problem.url,
-                generate_misattempt_msg(),
-                submission.url
-            )
-        })
+    let mut embed = submission.render_embed().author(author);
+
+    // Failures always link to the submission, for everyone to see what went wrong.
+    if has_link || !submission.accepted {
+        embed = embed.field("Submission", format!("[View]({})", submission.url), true);
+        text += &format!("\n\t{}", submission.url);
     }
+
+    if !notes.is_empty() {
+        embed = embed.field("Lists", notes.join("\n"), true);
+        text += &format!("\n\t📋 {}", notes.join(", "));
+    }
+
+    Some((embed, text))
 }
 
 fn generate_misattempt_msg() -> String {
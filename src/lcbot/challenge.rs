@@ -0,0 +1,80 @@
+use crate::lcdb::Store;
+use crate::models::{AnnouncementKind, ChallengeOutcome, Submission};
+
+use anyhow::Result;
+
+// Challenges only expire 5 minutes (in milliseconds) after their deadline, so that solves made
+// just in time have a chance to be polled first.
+const EXPIRY_GRACE: usize = 5 * 60 * 1000;
+
+/// Settles every open challenge that `submission` (if accepted) wins, and announces it in the
+/// guild it was issued in.
+pub async fn resolve(ctx: &serenity::client::Context, store: &dyn Store, submission: &Submission)
+    -> Result<()>
+{
+    if !submission.accepted {
+        return Ok(());
+    }
+
+    let won = store
+        .query_open_challenges()?
+        .into_iter()
+        .filter(|challenge| challenge.challenged == submission.username)
+        .filter(|challenge| {
+            // Challenges hold whatever the challenger typed, which may be a title or a slug.
+            challenge.problem.eq_ignore_ascii_case(&submission.problem.title)
+                || challenge.problem.eq_ignore_ascii_case(&submission.problem.url)
+        })
+        .filter(|challenge| challenge.is_won_at(submission.timestamp));
+
+    for challenge in won {
+        store.resolve_challenge(challenge.id, ChallengeOutcome::Won, submission.timestamp)?;
+        log::info!("{} won challenge {} of guild {}.",
+                   challenge.challenged, challenge.id, challenge.guild_id);
+
+        let record = store.query_challenge_record(challenge.guild_id, &challenge.challenged)?;
+        let msg = format!(
+            "🏆 **{}** took on **{}**'s challenge and solved [{}]({}) in time! They're now {record} \
+             in challenges.",
+            challenge.challenged, challenge.challenger, submission.problem.title,
+            submission.problem.link());
+
+        let result = super::announce(
+            ctx, store, challenge.guild_id, AnnouncementKind::Challenges, &msg).await;
+        if let Err(err) = result {
+            log::error!("Couldn't announce challenge {} won: {err}", challenge.id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Settles every open challenge whose deadline has passed by `now` (in milliseconds) as expired,
+/// and announces it in the guild it was issued in.
+pub async fn expire_due(ctx: &serenity::client::Context, store: &dyn Store, now: usize)
+    -> Result<()>
+{
+    let expired = store
+        .query_open_challenges()?
+        .into_iter()
+        .take_while(|challenge| challenge.deadline + EXPIRY_GRACE < now);
+
+    for challenge in expired {
+        store.resolve_challenge(challenge.id, ChallengeOutcome::Expired, challenge.deadline)?;
+        log::info!("Challenge {} of guild {} expired.", challenge.id, challenge.guild_id);
+
+        let record = store.query_challenge_record(challenge.guild_id, &challenge.challenger)?;
+        let msg = format!(
+            "⌛ **{}** didn't solve **{}** in time: **{}** wins the challenge, and is now {record} \
+             in challenges.",
+            challenge.challenged, challenge.problem, challenge.challenger);
+
+        let result = super::announce(
+            ctx, store, challenge.guild_id, AnnouncementKind::Challenges, &msg).await;
+        if let Err(err) = result {
+            log::error!("Couldn't announce challenge {} expired: {err}", challenge.id);
+        }
+    }
+
+    Ok(())
+}
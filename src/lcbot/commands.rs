@@ -1,68 +1,379 @@
 use crate::lcapi;
-use crate::lcdb;
-use crate::models::AnnouncementPreferences;
+use crate::lcdb::Store;
+use crate::models::{
+    AnnouncementKind, AnnouncementPreferences, Challenge, ChallengeOutcome, GuildSettings,
+    LeaderboardPeriod, MAX_STREAK_FREEZES, RecapPeriod, Render, STREAK_FREEZE_DAYS, SeasonLength,
+    StreakReminder, Submission, week_start,
+};
+
+use super::compare;
+use super::goal;
+use super::lists;
+use super::leaderboard;
+use super::recap;
+use super::season;
+use super::response::Response;
 
 use anyhow::{Context, Result, anyhow};
 use itertools::Itertools;
+use serenity::builder::{
+    CreateActionRow, CreateAutocompleteResponse, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, EditInteractionResponse,
+};
+use serenity::model::application::{ButtonStyle, CommandInteraction, ComponentInteraction};
 use serenity::model::channel::Message;
 
+mod invocation;
+use invocation::Invocation;
+
+mod parser;
+use parser::{Args, Value};
+
+mod permissions;
+use permissions::Permission;
+
+mod ratelimit;
+use ratelimit::LIMITER;
+
+mod registry;
+use registry::CommandKind;
+
 const MAX_CMD_LENGTH: usize = 12;
 
+// Custom IDs of the buttons under an untrack confirmation.
+const UNTRACK_CONFIRM: &str = "untrack_confirm";
+const UNTRACK_CANCEL: &str = "untrack_cancel";
+
+// Untrack confirmations can be answered for 5 minutes, or 300 seconds
+const UNTRACK_CONFIRM_TIMEOUT_SECS: i64 = 300;
+
+// Discord shows at most 25 autocomplete suggestions.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+const MAX_PREFIX_LENGTH: usize = 8;
+
+// Used when neither the guild nor $BOT_CALL_TOKEN sets a prefix.
+const DEFAULT_PREFIX: &str = "$";
+
+// Digests are merged over at most 1 hour, or 3600 seconds
+const MAX_DIGEST_WINDOW_SECS: u64 = 3600;
+
+// Challenges have to be solved within a day, unless given longer (up to 30 days).
+const DEFAULT_CHALLENGE_DAYS: i64 = 1;
+const MAX_CHALLENGE_DAYS: i64 = 30;
+
+const MAX_GOAL_COUNT: u64 = 10_000;
+
+const MAX_LIST_NAME_LENGTH: usize = 32;
+// How many of the most cheered users `kudos` shows.
+const MAX_KUDOS_USERS: usize = 10;
+
+pub use registry::slash_commands;
+
 pub struct Commands;
 impl Commands {
-    pub async fn run_command(ctx: &serenity::client::Context, msg: &Message) -> Result<String> {
-        let react_ok = async || -> Result<String> {
-            msg.react(
-                &ctx.http,
-                serenity::all::ReactionType::Unicode(String::from("✅")),
-            )
+    /// Runs a command from a message, e.g. `$audit leek` or `@LeekBot audit leek`.
+    /// Messages that aren't commands are left alone (and get an empty response).
+    pub async fn run_command(
+        ctx: &serenity::client::Context,
+        msg: &Message,
+        store: &dyn Store) -> Result<String>
+    {
+        let prefix = guild_prefix(store, msg.guild_id.map(|id| id.get()))?;
+        let Some(input) = Self::strip_prefix(ctx, msg, &prefix) else {
+            return Ok(String::from(""));
+        };
+
+        // Muted members are ignored outright: answering them is what they'd be after.
+        let author = msg.author.id.get();
+        if !is_owner(author) && LIMITER.check_muted(author).is_err() {
+            return Ok(String::from(""));
+        }
+
+        // Split what's left into the command (first word) and its arguments (the rest).
+        let (command, input) = input
+            .split_once(char::is_whitespace)
+            .unwrap_or((input, ""));
+
+        let Some(spec) = registry::find(command) else {
+            if Commands::is_valid_cmd(command) {
+                log::info!("User submitted unknown command: {}", command);
+                return Err(Self::strike(author, anyhow!(
+                    "No such command found: {}, see `{}help` for commands.",
+                    command,
+                    prefix
+                )));
+            } else {
+                log::info!("User submitted invalid command: {}", command);
+                return Err(Self::strike(author, anyhow!("Invalid command syntax.")));
+            }
+        };
+        let args = parser::parse(spec, input).map_err(|err| {
+            Self::strike(author, anyhow!("{err}\nExpected usage: {}", spec.usage(&prefix)))
+        })?;
+
+        Self::execute(ctx, Invocation::Message { msg, prefix: &prefix }, spec, args, store).await
+    }
+
+    /// Strips the command prefix off a message: either the guild's prefix, or a mention of the
+    /// bot. Returns None if the message isn't a command.
+    fn strip_prefix<'a>(ctx: &serenity::client::Context, msg: &'a Message, prefix: &str)
+        -> Option<&'a str>
+    {
+        // Bots don't get to run commands (least of all this one).
+        if msg.author.bot {
+            return None;
+        }
+
+        let bot_id = ctx.cache.current_user().id;
+        let input = [format!("<@{bot_id}>"), format!("<@!{bot_id}>"), String::from(prefix)]
+            .iter()
+            .find_map(|prefix| msg.content.strip_prefix(prefix.as_str()))?
+            .trim_start();
+
+        (!input.is_empty()).then_some(input)
+    }
+
+    /// Runs a slash command, replying to it once it's done.
+    pub async fn run_slash_command(
+        ctx: &serenity::client::Context,
+        command: &CommandInteraction,
+        store: &dyn Store) -> Result<()>
+    {
+        let spec = registry::find(&command.data.name)
+            .with_context(|| format!("Unknown slash command: {}", command.data.name))?;
+
+        // Commands can take longer than Discord waits for a reply (LeetCode is slow), so
+        // acknowledge it right away and fill in the reply later.
+        match spec.ephemeral {
+            true => command.defer_ephemeral(&ctx.http).await?,
+            false => command.defer(&ctx.http).await?,
+        }
+
+        let author = command.user.id.get();
+        let result = async {
+            if !is_owner(author) {
+                LIMITER.check_muted(author)?;
+            }
+
+            let args = parser::parse_slash(spec, &command.data.options())
+                .map_err(|err| anyhow!("{err}\nExpected usage: {}", spec.usage("/")))?;
+            Self::execute(ctx, Invocation::Slash(command), spec, args, store).await
+        }.await;
+        let response = result.unwrap_or_else(|err| {
+            log::error!("{err}");
+            format!("Error: {err}")
+        });
+
+        // An empty response means the command already replied by itself.
+        let response = Response::new(&response);
+        if !response.is_empty() {
+            let (content, components) = response.first_page();
+            command
+                .edit_response(&ctx.http, EditInteractionResponse::new()
+                    .content(content)
+                    .components(components))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Suggests values for whichever slash command option is being typed: tracked usernames for
+    /// usernames, and known problems for problem titles.
+    pub async fn autocomplete(
+        ctx: &serenity::client::Context,
+        command: &CommandInteraction,
+        store: &dyn Store) -> Result<()>
+    {
+        let Some(spec) = registry::find(&command.data.name) else {
+            return Ok(());
+        };
+        let Some(focused) = command.data.autocomplete() else {
+            return Ok(());
+        };
+
+        let search = focused.value.to_lowercase();
+        let suggestions = match spec.find_slash_option(focused.name) {
+            Some((_, registry::ArgKind::User)) => {
+                let users = match command.guild_id {
+                    Some(guild_id) => store.query_guild_tracked_users(guild_id.get())?,
+                    None => store.query_tracked_users()?,
+                };
+
+                users
+                    .into_iter()
+                    .map(|user| user.username)
+                    .filter(|username| username.to_lowercase().contains(&search))
+                    .sorted()
+                    .take(MAX_AUTOCOMPLETE_CHOICES)
+                    .collect()
+            }
+            Some((_, registry::ArgKind::Problem)) => {
+                store.query_problem_titles(&search, MAX_AUTOCOMPLETE_CHOICES)?
+            }
+            _ => Vec::new(),
+        };
+
+        let mut response = CreateAutocompleteResponse::new();
+        for suggestion in suggestions {
+            response = response.add_string_choice(suggestion.clone(), suggestion);
+        }
+        command
+            .create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
             .await?;
 
-            Ok(String::from(""))
+        Ok(())
+    }
+
+    /// Runs a command, wherever it came from. Returns what to reply with, if anything.
+    async fn execute(
+        ctx: &serenity::client::Context,
+        invocation: Invocation<'_>,
+        spec: &registry::CommandSpec,
+        args: Args,
+        store: &dyn Store) -> Result<String>
+    {
+        let react_ok = async || invocation.react_ok(ctx).await;
+        let prefix = invocation.prefix();
+        let usage = || anyhow!("Expected usage: {}", spec.usage(prefix));
+        let author = invocation.author();
+
+        // Most commands act on the tracklist of the guild they were called from.
+        let guild_id = || -> Result<u64> {
+            invocation
+                .guild_id()
+                .map(|id| id.get())
+                .context("This command can only be used in a server.")
         };
 
-        // Split the message's content (on whitespace) into:
-        // - The command (first token)
-        // - Its parameters (all tokens afterwards)
+        // Make sure the caller is allowed to run this. Only look them up if we need to,
+        // since it takes a round trip to Discord.
+        let caller = match spec.permission {
+            Permission::Everyone => Permission::Everyone,
+            _ => Permission::of_author(ctx, &invocation, store).await?,
+        };
+        spec.permission.check(caller, spec.name)?;
 
-        // Skip the first letter for the command: it's the call token
-        let input = String::from(&msg.content[1..]); 
-        let split_tokens = input.split_whitespace().collect::<Vec<_>>();
-        let (&[command], parameters) = split_tokens.split_at(1) else {
-            return Err(anyhow!("easd"));
+        // Keep everyone (but the owner) from running commands faster than LeetCode keeps up.
+        // Only commands they may run count: being refused doesn't start a cooldown.
+        if !is_owner(author.id.get()) {
+            LIMITER.check_cooldown(author.id.get(), spec)?;
+        }
+        let _permit = LIMITER.acquire().await?;
+
+        let check_self = |username: &str| {
+            permissions::check_self(store, author.id.get(), caller, username, prefix)
+        };
+        let resolve_username = || {
+            Self::resolve_username(store, author.id.get(), args.get("username|@member"), prefix)
         };
 
         // Execute the command
-        let result: String = match command {
-            "audit" => {
-                let username = parameters
-                    .first()
-                    .context("Expected username for audit, got none.")?
-                    .to_string();
+        let result: String = match spec.kind {
+            CommandKind::Audit => {
+                let username = resolve_username()?;
 
                 let user = lcapi::fetch_user(&username).await?;
-                let mut output = format!("{user}\n");
-                if let Some(prefs) = lcdb::query_user_preferences(&user)? {
-                    if let Some(announcement_prefs) = prefs.announcement {
-                        output += "This user is currently being tracked.\n";
-                        output += &format!("Failures are {}announced.\n",
-                            if announcement_prefs.announce_failures { "" } else { "not " }
-                        );
-                        output += &format!("Submission links are {}abled.\n",
-                            if announcement_prefs.has_submission_link { "en" } else { "dis" }
-                        )
+                let mut tracking = String::new();
+                if let Some(guild_id) = invocation.guild_id().map(|id| id.get()) {
+                    // Untracked users keep their preferences, in case they're tracked again.
+                    match store.query_user_preferences(guild_id, &user)? {
+                        Some(prefs) if prefs.tracked => {
+                            tracking += "This user is currently being tracked.\n";
+                            match prefs.announcement {
+                                Some(announcement) => {
+                                    tracking += &format!("Failures are {}announced.\n",
+                                        if announcement.announce_failures { "" } else { "not " }
+                                    );
+                                    tracking += &format!("Submission links are {}abled.\n",
+                                        if announcement.has_submission_link { "en" } else { "dis" }
+                                    )
+                                }
+                                None => tracking += "Their submissions are not announced.\n",
+                            }
+                        }
+                        _ => tracking += "This user is not currently being tracked.",
                     }
                 }
-                else {
-                    output += "This user is not currently being tracked.";
+
+                let text = format!("{}\n{tracking}", user.render_text());
+                let mut embed = user.render_embed();
+                if let Ok(Some(avatar)) = lcapi::fetch_avatar(&username).await {
+                    embed = embed.thumbnail(avatar);
+                }
+                if !tracking.is_empty() {
+                    embed = embed.field("Tracking", tracking, false);
                 }
 
-                output
+                invocation.reply_with_embed(ctx, embed, text).await?;
+                String::from("")
             }
-            "recent" => Self::get_recently_completed(parameters[0]).await?,
-            "tracklist" => {
+            CommandKind::Recent => {
+                let username = resolve_username()?;
+                let mut submission = Self::get_recently_completed(&username).await?;
+
+                // LeetCode doesn't say how hard recent problems are, but we may know.
+                if let Some(problem) = store.query_problem(&submission.problem.title)? {
+                    submission.problem.difficulty = problem.difficulty;
+                }
+
+                let embed = submission.render_embed();
+                invocation.reply_with_embed(ctx, embed, submission.render_text()).await?;
+                String::from("")
+            }
+            CommandKind::Link => {
+                let username = args.text("username").ok_or_else(usage)?;
+
+                // Make sure the account actually exists before linking it.
+                let user = lcapi::fetch_user(username).await?;
+                store.link_account(author.id.get(), &user.username)?;
+                log::info!("Linked {} to LeetCode account {username}", author.name);
+
+                react_ok().await?
+            }
+            CommandKind::Verify => {
+                let t = prefix;
+                let discord_id = author.id.get();
+
+                match args.text("username") {
+                    // Start a verification: hand out a token for the user to put in their bio.
+                    Some(username) => {
+                        let user = lcapi::fetch_user(username).await?;
+                        let token = generate_verification_token();
+                        store.insert_verification(discord_id, &user.username, &token)?;
+
+                        format!("To prove that you own **{username}**, add `{token}` anywhere in \
+                                 the \"About me\" of your LeetCode profile, then run `{t}verify` \
+                                 again within the hour. You can remove it afterwards.")
+                    }
+                    // Finish a verification: check that the token made it into their bio.
+                    None => {
+                        let verification = store
+                            .query_verification(discord_id)?
+                            .with_context(|| format!("You have no verification in progress; \
+                                                      start one with `{t}verify <username>`."))?;
+                        let username = &verification.username;
+
+                        let about_me = lcapi::fetch_about_me(username).await?;
+                        if !about_me.contains(&verification.token) {
+                            return Err(anyhow!("Couldn't find `{}` in the \"About me\" of \
+                                                {username} yet. LeetCode can take a moment to \
+                                                update, so try again shortly.",
+                                               verification.token));
+                        }
+
+                        store.complete_verification(discord_id)?;
+                        log::info!("{} verified ownership of LeetCode account {username}",
+                                   author.name);
+
+                        format!("Verified! Your Discord account is now linked to **{username}**.")
+                    }
+                }
+            }
+            CommandKind::Tracklist => {
                 let mut output = String::from("**Tracked users:**");
-                let users = lcdb::query_tracked_users();
+                let users = store.query_guild_tracked_users(guild_id()?);
                 match users {
                     Ok(users) => {
                         for user in users {
@@ -77,155 +388,745 @@ impl Commands {
 
                 output
             }
-            "track" => {
-                let username = parameters
-                    .first()
-                    .context("Expected username for tracking, got none.")?
-                    .to_string();
+            CommandKind::Track => {
+                let username = resolve_username()?;
+                check_self(&username)?;
 
+                let guild_id = guild_id()?;
                 let user = lcapi::fetch_user(&username).await?;
-                lcdb::track_user(&user)
+                store.track_user(guild_id, &user)
                     .inspect_err(|_| log::error!("Could not track user {username}"))?;
 
+                // The first time a guild tracks someone, announce where it was asked for.
+                let mut settings = store.query_guild_settings(guild_id)?;
+                if settings.announcements_channel.is_none() {
+                    settings.announcements_channel = Some(invocation.channel_id().get());
+                    store.update_guild_settings(guild_id, &settings)?;
+
+                    react_ok().await?;
+                    String::from("Announcements will be posted in this channel.")
+                } else {
+                    react_ok().await?
+                }
+            }
+            CommandKind::Untrack => {
+                let username = resolve_username()?;
+                check_self(&username)?;
+
+                let guild_id = guild_id()?;
+                let is_tracked = match store.query_user(&username)? {
+                    Some(user) => store.is_tracked(guild_id, &user)?,
+                    None => false,
+                };
+                if !is_tracked {
+                    return Err(anyhow!("{username} is not currently being tracked."));
+                }
+
+                // Untracking is a two-step affair: it only happens once the buttons below are
+                // answered by whoever asked for it (see `handle_component`).
+                let requester = author.id.get();
+                let buttons = CreateActionRow::Buttons(vec![
+                    CreateButton::new(format!("{UNTRACK_CONFIRM}:{requester}:{username}"))
+                        .label("Untrack")
+                        .style(ButtonStyle::Danger),
+                    CreateButton::new(format!("{UNTRACK_CANCEL}:{requester}:{username}"))
+                        .label("Cancel")
+                        .style(ButtonStyle::Secondary),
+                ]);
+
+                let content = format!("Stop tracking **{username}**? Their submissions won't be \
+                                       announced here anymore, but their history is kept in case \
+                                       they're tracked again.");
+                invocation.reply_with_components(ctx, content, vec![buttons]).await?;
+
+                String::from("")
+            }
+            CommandKind::Prefs => {
+                let username = resolve_username()?;
+                if args.options().is_empty() {
+                    return Err(usage());
+                }
+
+                let guild_id = guild_id()?;
+
+                // Only users tracked here have preferences to change.
+                let not_tracked = || anyhow!("{username} isn't tracked here.");
+                let user = store.query_user(&username)?.ok_or_else(not_tracked)?;
+
+                check_self(&user.username)?;
+
+                let mut prefs = store
+                    .query_user_preferences(guild_id, &user)?
+                    .ok_or_else(not_tracked)?;
+                let mut announcement = prefs.announcement.unwrap_or(AnnouncementPreferences {
+                    announce_failures: false,
+                    has_submission_link: false,
+                    digest: false,
+                });
+
+                for (name, value) in args.options() {
+                    let Value::Bool(state) = *value else {
+                        return Err(usage());
+                    };
+
+                    match *name {
+                        "announce_fail" => announcement.announce_failures = state,
+                        "announce_link" => announcement.has_submission_link = state,
+                        "digest" => announcement.digest = state,
+                        _ => return Err(usage()),
+                    }
+
+                    log::info!("Updated {username}'s announcement preferences: {name} = {state}");
+                }
+
+                prefs.announcement = Some(announcement);
+                store.update_user_preferences(guild_id, &user, &prefs)?;
+
                 react_ok().await?
             }
-            "untrack" => {
-                String::from("`untrack` is currently temporarily disabled.")
-                // let username = parameters
-                //     .first()
-                //     .context("Expected username for untracking, got none.")?
-                //     .to_string();
-
-                // let user = lcapi::fetch_user(username).await?;
-                // lcdb::untrack_user(&user)?;
-
-                // msg.react(
-                //     &ctx.http,
-                //     serenity::all::ReactionType::Unicode(String::from("✅")),
-                // )
-                // .await?;
-                // String::from("")
-            }
-            "prefs" => {
-                let get_usage = || format!("Expected usage: `{}prefs <username> \
-                                            [announce_fail|announce_link]=[true|false]`",
-                                            getenv_call_token());
-
-                let (username, pref_changes) = parameters
-                    .split_first()
-                    .with_context(get_usage)?;
-
-                // Helps against a common pitfall with this command...
-                if pref_changes.contains(&"=") {
-                    return Err(anyhow!("{}\n (there mustn't be whitespace around the '`=`')", 
-                               get_usage()))
-                }
-
-                // Get the User object
-                let user = match lcdb::query_user(username)? {
-                    Some(user) => user,
-                    None => lcapi::fetch_user(username).await?
+            CommandKind::AdminRole => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                settings.admin_role = match args.get("@role|none") {
+                    Some(Value::Role(role)) => *role,
+                    _ => {
+                        return Ok(match settings.admin_role {
+                            Some(role) => format!("The admin role is <@&{role}>."),
+                            None => String::from("There is no admin role set."),
+                        })
+                    }
                 };
 
-                let mut prefs = lcdb::query_user_preferences(&user)?.unwrap_or_default();
-                let mut msgs = Vec::new();
-
-                for change in pref_changes {
-                    let change_tuple = change
-                        .split("=")
-                        .next_tuple()
-                        .map(|(cmd, state)| (cmd.trim(), state.trim()));
-
-                    msgs.push(match change_tuple {
-                        Some(("announce_fail", state @ ("true" | "false"))) => {
-                            prefs.announcement = Some(prefs.announcement.map_or_else(
-                                || AnnouncementPreferences {
-                                    announce_failures: state == "true",
-                                    has_submission_link: false },
-                                |a| AnnouncementPreferences {
-                                    announce_failures: state == "true",
-                                    has_submission_link: a.has_submission_link }
-                            ));
-
-                            lcdb::update_user_preferences(&user, &prefs)?;
-                            log::info!("Updated {username}'s announcement preferences: \
-                                    announce_fail = {state}");
-
-                            react_ok().await?
+                store.update_guild_settings(guild_id, &settings)?;
+                log::info!("Set the admin role of guild {guild_id} to {:?}", settings.admin_role);
+
+                react_ok().await?
+            }
+            CommandKind::SetChannel => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                let channel = match args.get("#channel|none") {
+                    Some(Value::Channel(channel)) => *channel,
+                    _ => Some(invocation.channel_id().get()),
+                };
+
+                match args.text("kind").and_then(AnnouncementKind::from_name) {
+                    Some(kind) => settings.set_channel_override(kind, channel),
+                    // `all`: everything goes to the one channel.
+                    None => {
+                        settings.announcements_channel = channel;
+                        for kind in AnnouncementKind::ALL {
+                            settings.set_channel_override(kind, None);
                         }
-                        Some(("announce_link", state @ ("true" | "false"))) => {
-                            prefs.announcement = Some(prefs.announcement.map_or_else(
-                                || AnnouncementPreferences {
-                                    announce_failures: false,
-                                    has_submission_link: state == "true"},
-                                |a| AnnouncementPreferences {
-                                    announce_failures: a.announce_failures,
-                                    has_submission_link: state == "true"}
-                            ));
-
-                            lcdb::update_user_preferences(&user, &prefs)?;
-                            log::info!("Updated {username}'s announcement preferences: \
-                                    announce_link = {state}");
-
-                            react_ok().await?
+                    }
+                }
+
+                store.update_guild_settings(guild_id, &settings)?;
+                log::info!("Set announcement channels of guild {guild_id}: {settings:?}");
+
+                announcement_channels(&settings)
+            }
+            CommandKind::Help => match args.text("command") {
+                Some(name) => registry::find(name)
+                    .with_context(|| format!("No such command found: {name}."))?
+                    .details(prefix),
+                None => registry::help(prefix),
+            },
+            CommandKind::Prefix => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                settings.prefix = match args.text("prefix") {
+                    Some("reset") => None,
+                    Some(new_prefix) if new_prefix.chars().any(char::is_whitespace) => {
+                        return Err(anyhow!("Prefixes can't have spaces in them."));
+                    }
+                    Some(new_prefix) if new_prefix.chars().count() > MAX_PREFIX_LENGTH => {
+                        return Err(anyhow!("Prefixes can be at most {MAX_PREFIX_LENGTH} \
+                                            characters long."));
+                    }
+                    Some(new_prefix) => Some(String::from(new_prefix)),
+                    None => {
+                        return Ok(format!("The prefix here is `{}`. Mentioning me works too.",
+                                          guild_prefix(store, Some(guild_id))?));
+                    }
+                };
+
+                store.update_guild_settings(guild_id, &settings)?;
+                log::info!("Set the prefix of guild {guild_id} to {:?}", settings.prefix);
+
+                format!("Commands now start with `{}` here.", guild_prefix(store, Some(guild_id))?)
+            }
+            CommandKind::Digest => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                settings.digest_window = match args.text("window|off") {
+                    Some("off") => None,
+                    Some(window) => {
+                        let window = parser::parse_value(registry::ArgKind::Duration, window)?;
+                        let Value::Duration(window) = window else {
+                            return Err(usage());
+                        };
+                        let seconds = window.num_seconds().unsigned_abs();
+                        if seconds > MAX_DIGEST_WINDOW_SECS {
+                            return Err(anyhow!("Digests can be merged over at most {}.",
+                                               format_seconds(MAX_DIGEST_WINDOW_SECS)));
                         }
-                        Some((cmd @ ("announce_fail" | "announce_link"), state)) => {
-                            return Err(anyhow!("Cannot set {cmd} to {state}: \n{}", get_usage()))
+                        Some(seconds)
+                    }
+                    None => {
+                        return Ok(match settings.digest_window {
+                            Some(seconds) => format!("Submissions are digested over {} here.",
+                                                     format_seconds(seconds)),
+                            None => String::from("Submissions are only digested for users who \
+                                                  asked for it here."),
+                        });
+                    }
+                };
+
+                store.update_guild_settings(guild_id, &settings)?;
+                log::info!("Set the digest window of guild {guild_id} to {:?}",
+                           settings.digest_window);
+
+                react_ok().await?
+            }
+            CommandKind::Recap => {
+                let guild_id = guild_id()?;
+                let period = match args.text("period") {
+                    Some("weekly") => RecapPeriod::Weekly,
+                    _ => RecapPeriod::Daily,
+                };
+
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                let recap = recap::build(store, guild_id, period, now)?;
+                invocation.reply_with_embed(ctx, recap.render_embed(), recap.render_text()).await?;
+                String::from("")
+            }
+            CommandKind::RecapSchedule => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                let recaps = args.text("recaps");
+                if recaps.is_none() && args.options().is_empty() {
+                    return Ok(recap_schedule(&settings));
+                }
+
+                if let Some(recaps) = recaps {
+                    settings.daily_recap = matches!(recaps, "daily" | "both");
+                    settings.weekly_recap = matches!(recaps, "weekly" | "both");
+                }
+                for (name, value) in args.options() {
+                    match (*name, value) {
+                        ("hour", Value::Integer(hour)) => settings.recap_hour = *hour as u32,
+                        ("day", Value::Text(day)) => {
+                            let day = registry::WEEKDAYS.iter().position(|d| d == day);
+                            settings.recap_day = day.ok_or_else(usage)? as u32;
                         }
-                        Some((unknown_cmd, _)) => {
-                            return Err(anyhow!("Unknown announcement preference: {unknown_cmd} \n\
-                                                {}", get_usage()))
+                        _ => return Err(usage()),
+                    }
+                }
+
+                store.update_guild_settings(guild_id, &settings)?;
+                log::info!("Set the recap schedule of guild {guild_id}: {settings:?}");
+
+                recap_schedule(&settings)
+            }
+            CommandKind::Leaderboard => {
+                let guild_id = guild_id()?;
+                let period = args
+                    .text("period")
+                    .and_then(LeaderboardPeriod::from_name)
+                    .unwrap_or(LeaderboardPeriod::Week);
+
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                let leaderboard = leaderboard::build(store, guild_id, period, now)?;
+                invocation
+                    .reply_with_embed(ctx, leaderboard.render_embed(), leaderboard.render_text())
+                    .await?;
+                String::from("")
+            }
+            CommandKind::PinLeaderboard => {
+                let guild_id = guild_id()?;
+                let period = match args.text("period") {
+                    Some("off") => {
+                        leaderboard::unpin(store, guild_id)?;
+                        return Ok(String::from("The pinned leaderboard won't be updated anymore."));
+                    }
+                    period => period
+                        .and_then(LeaderboardPeriod::from_name)
+                        .unwrap_or(LeaderboardPeriod::Week),
+                };
+
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                let channel = invocation.channel_id();
+                let pinned = leaderboard::pin(ctx, store, guild_id, channel, period, now).await?;
+                log::info!("Pinned a {} leaderboard in guild {guild_id}", period.name());
+
+                match pinned {
+                    true => react_ok().await?,
+                    false => String::from("I couldn't pin the leaderboard (I need the Manage \
+                                           Messages permission), but I'll keep it up to date."),
+                }
+            }
+            CommandKind::Points => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                if args.text("reset").is_some() {
+                    settings.easy_points = None;
+                    settings.medium_points = None;
+                    settings.hard_points = None;
+                }
+                for (name, value) in args.options() {
+                    let Value::Integer(points) = *value else {
+                        return Err(usage());
+                    };
+
+                    match *name {
+                        "easy" => settings.easy_points = Some(points),
+                        "medium" => settings.medium_points = Some(points),
+                        "hard" => settings.hard_points = Some(points),
+                        _ => return Err(usage()),
+                    }
+                }
+
+                if args.text("reset").is_some() || !args.options().is_empty() {
+                    store.update_guild_settings(guild_id, &settings)?;
+                    log::info!("Set the points of guild {guild_id} to {:?}",
+                               settings.point_weights());
+
+                    let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                    leaderboard::refresh_pinned(ctx, store, guild_id, now).await?;
+                }
+
+                let [easy, medium, hard] = settings.point_weights();
+                format!("Easy solves are worth {easy} points, medium ones {medium} and hard ones \
+                         {hard}.")
+            }
+            CommandKind::Season => {
+                let guild_id = guild_id()?;
+                let settings = store.query_guild_settings(guild_id)?;
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+
+                let is_running = settings.current_season().is_some();
+                let season = match args.get("number") {
+                    Some(Value::Integer(number))
+                        if !is_running || *number != settings.season_number =>
+                    {
+                        store
+                            .query_season(guild_id, *number)?
+                            .with_context(|| format!("There's no season {number} to look back \
+                                                      on yet."))?
+                    }
+                    _ => season::current(store, guild_id, now)?.with_context(|| format!(
+                        "Seasons aren't being run here. An admin can start them with \
+                         `{prefix}seasons month`."))?,
+                };
+
+                invocation.reply_with_embed(ctx, season.render_embed(), season.render_text()).await?;
+                String::from("")
+            }
+            CommandKind::Seasons => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                match args.text("length") {
+                    None => {}
+                    Some("off") => {
+                        settings.season_length = None;
+                        settings.season_start = None;
+                    }
+                    Some(length) => {
+                        let length = SeasonLength::from_name(length).ok_or_else(usage)?;
+
+                        // Seasons start at midnight, like the scheduler that ends them.
+                        if settings.current_season().is_none() {
+                            let today = chrono::Utc::now().date_naive();
+                            let midnight = today.and_time(Default::default()).and_utc();
+                            settings.season_number += 1;
+                            settings.season_start = Some(midnight.timestamp_millis() as usize);
                         }
-                        None => {
-                            return Err(anyhow!("Unknown announcement preference. \n{}", 
-                                               get_usage()))
+                        settings.season_length = Some(String::from(length.name()));
+                    }
+                }
+
+                if args.text("length").is_some() {
+                    store.update_guild_settings(guild_id, &settings)?;
+                    log::info!("Set the seasons of guild {guild_id} to {:?}",
+                               settings.season_length);
+                }
+
+                match settings.current_season() {
+                    Some((length, start)) => format!(
+                        "Seasons last a {}. Season {} ends <t:{}:R>.",
+                        length.name(), settings.season_number, length.end(start) / 1000),
+                    None => String::from("Seasons aren't being run here."),
+                }
+            }
+            CommandKind::Compare => {
+                let author_id = author.id.get();
+                let left =
+                    Self::resolve_username(store, author_id, args.get("user1|@member"), prefix)?;
+                let right =
+                    Self::resolve_username(store, author_id, args.get("user2|@member"), prefix)?;
+                if left.eq_ignore_ascii_case(&right) {
+                    return Err(anyhow!("Comparing {left} to themself would be a tie."));
+                }
+
+                let (left, right) =
+                    tokio::try_join!(lcapi::fetch_user(&left), lcapi::fetch_user(&right))?;
+
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                let comparison = compare::build(store, left, right, now)?;
+                invocation
+                    .reply_with_embed(ctx, comparison.render_embed(), comparison.render_text())
+                    .await?;
+                String::from("")
+            }
+            CommandKind::Challenge => {
+                let guild_id = guild_id()?;
+                // Challenges count towards the challenger's record, so it has to be theirs.
+                let challenger = Self::resolve_username(store, author.id.get(), None, prefix)?;
+                if !store.is_verified_owner(author.id.get(), &challenger)? {
+                    return Err(anyhow!("⛔ Challenges are issued from your verified account, and \
+                                        {challenger} isn't verified yet (see `{prefix}verify`)."));
+                }
+                let username = resolve_username()?;
+
+                // Only tracked users' solves get polled, so only they can win challenges.
+                let challenged = match store.query_user(&username)? {
+                    Some(user) if store.is_tracked(guild_id, &user)? => user.username,
+                    _ => return Err(anyhow!("{username} isn't tracked here, so I'd never see them \
+                                             solve it (see `{prefix}track`).")),
+                };
+                if challenged.eq_ignore_ascii_case(&challenger) {
+                    return Err(anyhow!("You can't challenge yourself."));
+                }
+
+                // Use the problem's proper title if it's known, as shown on submissions.
+                let problem = args.text("problem").ok_or_else(usage)?;
+                let problem = store
+                    .query_problem_titles(problem, MAX_AUTOCOMPLETE_CHOICES)?
+                    .into_iter()
+                    .find(|title| title.eq_ignore_ascii_case(problem))
+                    .unwrap_or_else(|| problem.to_owned());
+
+                let time_limit = match args.get("deadline") {
+                    Some(Value::Duration(time_limit)) => *time_limit,
+                    _ => chrono::TimeDelta::days(DEFAULT_CHALLENGE_DAYS),
+                };
+                if time_limit > chrono::TimeDelta::days(MAX_CHALLENGE_DAYS) {
+                    return Err(anyhow!("Challenges can last at most {MAX_CHALLENGE_DAYS} days."));
+                }
+
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                let deadline = now + time_limit.num_milliseconds() as usize;
+                let challenge = Challenge {
+                    id: 0,
+                    guild_id,
+                    challenger,
+                    challenged,
+                    problem,
+                    issued_at: now,
+                    deadline,
+                    outcome: ChallengeOutcome::Open,
+                    resolved_at: None,
+                };
+                let id = store.insert_challenge(&challenge)?;
+                log::info!("{} challenged {} to solve {} in guild {guild_id} (challenge {id})",
+                           challenge.challenger, challenge.challenged, challenge.problem);
+
+                // Only ping members who proved the account is theirs.
+                let challenged = match args.get("username|@member") {
+                    Some(Value::Member(discord_id))
+                        if store.is_verified_owner(*discord_id, &challenge.challenged)? =>
+                    {
+                        format!("<@{discord_id}>")
+                    }
+                    _ => format!("**{}**", challenge.challenged),
+                };
+                format!("⚔️ **{}** challenged {challenged} to solve **{}** by <t:{}:f> (<t:{}:R>)!",
+                        challenge.challenger, challenge.problem, deadline / 1000, deadline / 1000)
+            }
+            CommandKind::Challenges => {
+                let guild_id = guild_id()?;
+                let username = resolve_username()?;
+                let username = store
+                    .query_user(&username)?
+                    .map_or(username, |user| user.username);
+
+                let record = store.query_challenge_record(guild_id, &username)?;
+                let mut output = format!("**{username}** is {record} in challenges here.");
+
+                let open = store
+                    .query_open_challenges()?
+                    .into_iter()
+                    .filter(|challenge| challenge.guild_id == guild_id)
+                    .filter(|challenge| challenge.challenger == username
+                                        || challenge.challenged == username)
+                    .collect::<Vec<_>>();
+                if !open.is_empty() {
+                    output += "\n**Open challenges:**";
+                }
+                for challenge in open {
+                    output += &format!("\n\t{} → {}: **{}**, due <t:{}:R>",
+                                       challenge.challenger, challenge.challenged,
+                                       challenge.problem, challenge.deadline / 1000);
+                }
+
+                output
+            }
+            CommandKind::Goal => {
+                let goal = goal::build(store, guild_id()?, chrono::Utc::now())?
+                    .with_context(|| format!("There's no weekly goal here. An admin can set one \
+                                              with `{prefix}setgoal 50 medium`."))?;
+
+                invocation.reply_with_embed(ctx, goal.render_embed(), goal.render_text()).await?;
+                String::from("")
+            }
+            CommandKind::SetGoal => {
+                let guild_id = guild_id()?;
+                let mut settings = store.query_guild_settings(guild_id)?;
+
+                match args.text("count|off").ok_or_else(usage)? {
+                    "off" => {
+                        settings.goal_count = None;
+                        settings.goal_difficulty = None;
+                    }
+                    count => {
+                        let count = count
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|count| (1..=MAX_GOAL_COUNT).contains(count))
+                            .with_context(|| format!("Goals are a number of solves, from 1 to \
+                                                      {MAX_GOAL_COUNT}."))?;
+                        settings.goal_count = Some(count);
+                        settings.goal_difficulty = args
+                            .text("difficulty")
+                            .filter(|difficulty| *difficulty != "any")
+                            .map(String::from);
+                    }
+                }
+
+                // A new goal starts its milestones over.
+                settings.goal_milestone = 0;
+                settings.goal_milestone_at = None;
+                store.update_guild_settings(guild_id, &settings)?;
+                log::info!("Set the weekly goal of guild {guild_id} to {:?} {:?}",
+                           settings.goal_count, settings.goal_difficulty);
+
+                match goal::build(store, guild_id, chrono::Utc::now())? {
+                    Some(goal) => {
+                        invocation
+                            .reply_with_embed(ctx, goal.render_embed(), goal.render_text())
+                            .await?;
+                        String::from("")
+                    }
+                    None => String::from("There's no weekly goal anymore."),
+                }
+            }
+            CommandKind::ImportList => {
+                let guild_id = guild_id()?;
+                let name = args.text("name").ok_or_else(usage)?.to_lowercase();
+                if !is_valid_list_name(&name) {
+                    return Err(anyhow!("List names are up to {MAX_LIST_NAME_LENGTH} letters, \
+                                        digits, `-` or `_`."));
+                }
+
+                let file = match args.get("file") {
+                    Some(Value::Attachment(url)) => Some(url.as_str()),
+                    _ => invocation.attachment_url(),
+                };
+                let titles = match (file, lists::builtin(&name)) {
+                    (Some(url), _) => {
+                        let contents = reqwest::get(url).await?.error_for_status()?.text().await?;
+                        if contents.len() > lists::MAX_LIST_FILE_BYTES {
+                            return Err(anyhow!("That file is too big to be a problem list."));
                         }
-                    })
+                        lists::parse(&contents)?
+                    }
+                    (None, Some(builtin)) => builtin?,
+                    (None, None) => return Err(anyhow!(
+                        "Attach a JSON or CSV file of problem titles, or import a built-in list: \
+                         {}.", lists::builtin_names().map(|name| format!("`{name}`")).join(", "))),
+                };
+
+                let list = lists::import(store, guild_id, &name, titles)?;
+                format!("Imported **{name}**, {} problems long. Follow it with `{prefix}follow \
+                         {name}` to have your progress on it announced.", list.problems.len())
+            }
+            CommandKind::List => {
+                let guild_id = guild_id()?;
+                let name = args.text("name").ok_or_else(usage)?.to_lowercase();
+                let list = store.query_problem_list(guild_id, &name)?.with_context(|| {
+                    format!("There's no list called {name} here. Lists: {}",
+                            list_names(store, guild_id, prefix))
+                })?;
+
+                let now = chrono::Utc::now().timestamp_millis().max(0) as usize;
+                let progress = match args.get("username|@member") {
+                    Some(_) => {
+                        let username = resolve_username()?;
+                        let user = match store.query_user(&username)? {
+                            Some(user) => user,
+                            None => lcapi::fetch_user(&username).await?,
+                        };
+                        lists::user_progress(store, &list, &user, now)?
+                    }
+                    None => lists::progress(store, guild_id, &list, now)?,
+                };
+
+                invocation
+                    .reply_with_embed(ctx, progress.render_embed(), progress.render_text())
+                    .await?;
+                String::from("")
+            }
+            CommandKind::Follow | CommandKind::Unfollow => {
+                let guild_id = guild_id()?;
+                let username = resolve_username()?;
+                let username = store.query_user(&username)?.map_or(username, |user| user.username);
+                check_self(&username)?;
+
+                let name = args.text("name").ok_or_else(usage)?.to_lowercase();
+                let following = spec.kind == CommandKind::Follow;
+                if following && store.query_problem_list(guild_id, &name)?.is_none() {
+                    return Err(anyhow!("There's no list called {name} here. Lists: {}",
+                                       list_names(store, guild_id, prefix)));
                 }
 
-                msgs.join("\n")
+                store.update_list_follow(guild_id, &username, &name, following)?;
+                log::info!("{username} {} list {name} in guild {guild_id}",
+                           if following { "followed" } else { "unfollowed" });
+
+                react_ok().await?
             }
-            "help" => Self::get_help(),
-            "clanker" => String::from("call me clanker one more mf time"),
-            "insert" => {
-                if !is_debug_mode() {
-                    String::from("This command is only available in debug mode.")
-                } else {
-                    let (params, problem_name) = parameters.split_at_checked(2).context(
-                        "Expected usage: `!insert <username> <success|failure> <problem_name>`",
-                    )?;
+            CommandKind::Reminder => {
+                let discord_id = author.id.get();
+                let hours = match args.text("hours|off") {
+                    None => {
+                        return Ok(match store.query_streak_reminder(discord_id)? {
+                            Some(reminder) => describe_reminder(&reminder),
+                            None => String::from("You don't have a streak reminder."),
+                        });
+                    }
+                    Some("off") => {
+                        store.delete_streak_reminder(discord_id)?;
+                        return react_ok().await;
+                    }
+                    Some(hours) => hours
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|hours| (1..=23).contains(hours))
+                        .context("Reminders are sent from 1 to 23 hours before midnight UTC.")?,
+                };
 
-                    let username = params
-                        .first()
-                        .context("Expected username for tracking, got none.")?
-                        .to_string();
+                // Reminders are about the linked account's streak, so it has to be theirs.
+                let username = Self::resolve_username(store, discord_id, None, prefix)?;
+                if !store.is_verified_owner(discord_id, &username)? {
+                    return Err(anyhow!("⛔ Reminders are about your verified account's streak, \
+                                        and {username} isn't verified yet (see \
+                                        `{prefix}verify`)."));
+                }
+                let tracked = match store.query_user(&username)? {
+                    Some(user) => !store.query_tracking_guilds(&user)?.is_empty(),
+                    None => false,
+                };
+                if !tracked {
+                    return Err(anyhow!("{username} isn't tracked anywhere, so they don't have a \
+                                        streak to lose (see `{prefix}track`)."));
+                }
 
-                    let user = lcapi::fetch_user(&username).await?;
+                let channel_id = match args.text("where") {
+                    Some("here") => {
+                        guild_id()?;
+                        Some(invocation.channel_id().get())
+                    }
+                    _ => None,
+                };
 
-                    let success = parameters
-                        .get(1)
-                        .context("Expected problem result (success | failure), got none.")?
-                        .eq(&"success");
+                let reminder = StreakReminder { discord_id, hours_before: hours, channel_id };
+                store.update_streak_reminder(&reminder)?;
+                log::info!("Set the streak reminder of {}: {reminder:?}", author.name);
 
-                    let problem = problem_name.join(" ");
+                describe_reminder(&reminder)
+            }
+            CommandKind::Freezes => {
+                let username = resolve_username()?;
+                let Some(user) = store.query_user(&username)? else {
+                    return Err(anyhow!("{username} isn't tracked anywhere, so they don't have a \
+                                        streak to freeze (see `{prefix}track`)."));
+                };
 
-                    log::info!("Inserted fake submission: {problem}");
+                let freezes = store.query_streak_freezes(&user)?;
+                format!("**{}** has {freezes}/{} streak freezes. One is earned every {} days of \
+                         streak, and spent on a missed day instead of the streak.",
+                        user.username, MAX_STREAK_FREEZES, STREAK_FREEZE_DAYS)
+            }
+            CommandKind::Kudos => {
+                let guild_id = guild_id()?;
+                let now = chrono::Utc::now();
+                let until = now.timestamp_millis().max(0) as usize + 1;
+                let week = week_start(now).timestamp_millis().max(0) as usize;
 
-                    lcdb::insert_fake_submission(&user, problem, success)?;
+                if args.get("username|@member").is_none() {
+                    let totals = store.query_kudos_totals(guild_id, week, until)?;
+                    if totals.is_empty() {
+                        return Ok(String::from("Nobody got any kudos this week yet: react to \
+                                                someone's announcement to cheer them on!"));
+                    }
 
-                    react_ok().await?
+                    let mut output = String::from("**Most cheered this week:**");
+                    let top = totals.iter().take(MAX_KUDOS_USERS).enumerate();
+                    for (rank, (username, kudos)) in top {
+                        output += &format!("\n\t{}. {username}: {kudos} kudos", rank + 1);
+                    }
+                    return Ok(output);
                 }
+
+                let username = resolve_username()?;
+                let username = store
+                    .query_user(&username)?
+                    .map_or(username, |user| user.username);
+                let kudos_of = |since| -> Result<u64> {
+                    Ok(store
+                        .query_kudos_totals(guild_id, since, until)?
+                        .into_iter()
+                        .find(|(cheered, _)| *cheered == username)
+                        .map_or(0, |(_, kudos)| kudos))
+                };
+
+                format!("**{username}** got {} kudos here, {} of them this week.", kudos_of(0)?,
+                        kudos_of(week)?)
             }
-            _ => {
-                if Commands::is_valid_cmd(command) {
-                    log::info!("User submitted unknown command: {}", command);
-                    return Err(anyhow!(
-                        "No such command found: {}, see $help for commands.",
-                        command
-                    ));
+            CommandKind::Clanker => String::from("call me clanker one more mf time"),
+            CommandKind::Insert => {
+                if !is_debug_mode() {
+                    String::from("This command is only available in debug mode.")
                 } else {
-                    log::info!("User submitted invalid command: {}", command);
-                    return Err(anyhow!("Invalid command syntax."));
+                    let username = args.text("username").ok_or_else(usage)?;
+                    let user = lcapi::fetch_user(username).await?;
+                    let success = args.text("outcome") == Some("success");
+                    let problem = args.text("problem").ok_or_else(usage)?.to_owned();
+
+                    // Backdating helps with testing streaks.
+                    let now = chrono::Utc::now();
+                    let at = match args.options().last() {
+                        Some((_, Value::Duration(ago))) => {
+                            now.checked_sub_signed(*ago).ok_or_else(usage)?
+                        }
+                        Some((_, Value::Date(date))) => date.and_time(Default::default()).and_utc(),
+                        _ => now,
+                    };
+
+                    log::info!("Inserted fake submission: {problem}");
+
+                    store.insert_fake_submission(&user, problem, success,
+                                                 at.timestamp_millis().max(0) as usize)?;
+
+                    react_ok().await?
                 }
             }
         };
@@ -233,19 +1134,99 @@ impl Commands {
         Ok(result)
     }
 
-    async fn get_recently_completed(username: &str) -> Result<String> {
-        Ok(format!(
-            "{}",
-            lcapi::fetch_recently_completed(username)
-                .await?
-                .first()
-                .context(format!("No recently completed problems for {}", username))?
-        ))
+    /// Handles presses of the buttons attached to command responses.
+    pub async fn handle_component(
+        ctx: &serenity::client::Context,
+        component: &ComponentInteraction,
+        store: &dyn Store) -> Result<()>
+    {
+        let custom_id = &component.data.custom_id;
+        let mut fields = custom_id.splitn(3, ':');
+        let (Some(action @ (UNTRACK_CONFIRM | UNTRACK_CANCEL)), Some(requester), Some(username)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            log::warn!("[handle_component] Unknown component: {custom_id}");
+            return Ok(());
+        };
+
+        // Only whoever asked for the untrack (and was allowed to) gets to answer.
+        if component.user.id.to_string() != requester {
+            let response = CreateInteractionResponseMessage::new()
+                .content("Only the member who asked for this can answer it.")
+                .ephemeral(true);
+            component
+                .create_response(&ctx.http, CreateInteractionResponse::Message(response))
+                .await?;
+
+            return Ok(());
+        }
+
+        let age = chrono::Utc::now().timestamp() - component.message.timestamp.unix_timestamp();
+        let content = if age > UNTRACK_CONFIRM_TIMEOUT_SECS {
+            String::from("This confirmation has expired; run `untrack` again.")
+        } else if action == UNTRACK_CANCEL {
+            format!("Kept tracking **{username}**.")
+        } else {
+            let guild_id = component
+                .guild_id
+                .context("Untrack confirmations only exist in servers.")?
+                .get();
+            let user = store
+                .query_user(username)?
+                .with_context(|| format!("{username} is not in the database."))?;
+
+            store.untrack_user(guild_id, &user)?;
+            log::info!("{} untracked {username} in guild {guild_id}", component.user.name);
+
+            // If nobody's tracking them anymore, don't leave anything behind for the poller:
+            // it'd all get announced at once if they were tracked again.
+            if store.query_tracking_guilds(&user)?.is_empty() {
+                let settled = store.settle_uncached_submissions(&user)?;
+                log::debug!("[handle_component] Settled {settled} pending submissions for \
+                             {username}.");
+            }
+
+            format!("No longer tracking **{username}**.")
+        };
+
+        let response = CreateInteractionResponseMessage::new()
+            .content(content)
+            .components(vec![]);
+        component
+            .create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(response))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_recently_completed(username: &str) -> Result<Submission> {
+        lcapi::fetch_recently_completed(username)
+            .await?
+            .into_iter()
+            .next()
+            .context(format!("No recently completed problems for {}", username))
     }
 }
 
 /// Non-async helpers
 impl Commands {
+    /// Counts an invalid command against its author. If that gets them muted, they're told so
+    /// instead of what was wrong with the command.
+    fn strike(discord_id: u64, err: anyhow::Error) -> anyhow::Error {
+        if is_owner(discord_id) {
+            return err;
+        }
+
+        match LIMITER.strike(discord_id) {
+            Ok(true) => LIMITER.check_muted(discord_id).err().unwrap_or(err),
+            Ok(false) => err,
+            Err(strike_err) => {
+                log::error!("Couldn't count an invalid command against {discord_id}: {strike_err}");
+                err
+            }
+        }
+    }
+
     /// Ensures that the string slice conforms to C-like identifier regex
     fn is_valid_cmd(s: &str) -> bool {
         s.len() <= MAX_CMD_LENGTH
@@ -254,49 +1235,147 @@ impl Commands {
                 .is_match(s)
     }
 
-    /// Gets a help string. Should be updated after a new command is added
-    /// TODO: Generate automatically?
-    pub fn get_help() -> String {
-        let t = getenv_call_token();
-        format!(
-            r#"
-**Command List:**
-`{t}audit <leetcode username>`:  Get stats on a leetcode user.
-`{t}recent <leetcode username>`:  Get the most recent submission from a leetcode user.
-`{t}track <leetcode username>`:  Track a user. This will cause the bot to begin tracking submissions for this user.
-`{t}untrack <leetcode username>`:  Untrack a user.
-`{t}prefs <leetcode username>`: Modify announcement preferences for a user.
-`{t}tracklist`:  List all tracked users.
-`{t}help`:  Get information on supported commands
-"#,
-        )
+    /// Figures out which LeetCode username a command is about, given its (optional) argument:
+    /// - a LeetCode username is taken as-is,
+    /// - an @mention resolves to the mentioned member's linked account,
+    /// - no argument at all resolves to the caller's linked account.
+    ///
+    /// Links aren't proof of ownership: check `Store::is_verified_owner` wherever the account has
+    /// to really be theirs.
+    fn resolve_username(store: &dyn Store, author_id: u64, arg: Option<&Value>, prefix: &str)
+        -> Result<String>
+    {
+        let (discord_id, whose) = match arg {
+            Some(Value::Member(discord_id)) => (*discord_id, "That member hasn't"),
+            Some(Value::Text(username)) => return Ok(username.clone()),
+            _ => (author_id, "You haven't"),
+        };
+
+        store
+            .query_linked_username(discord_id)?
+            .with_context(|| format!("{whose} linked a LeetCode account yet \
+                                      (see `{prefix}link`)."))
     }
 }
 
-/// Get the call token from the environment (.env file)
+/// Generates a one-time token for account verification, e.g. `leek-4fQz81Ab`.
+fn generate_verification_token() -> String {
+    use rand::distr::{Alphanumeric, SampleString};
+    format!("leek-{}", Alphanumeric.sample_string(&mut rand::rng(), 8))
+}
+
+/// Get the bot owner's Discord ID from the environment (.env file), if set.
 ///
-/// # Panics
-/// If $BOT_CALL_TOKEN is not defined, or is more than a single character, will panic.
-pub fn getenv_call_token() -> char {
-    let env_token = std::env::var("BOT_CALL_TOKEN")
-        .unwrap_or_else(|_| {
-            log::error!("$BOT_CALL_TOKEN not defined. \n Please define a single-character \
-                         call-token (i.e., $ or !)");
-            panic!()
-        });
+/// The owner may run every command, in every guild.
+pub fn getenv_owner_id() -> Option<u64> {
+    let owner_id = std::env::var("BOT_OWNER_ID").ok()?;
+    owner_id
+        .parse()
+        .inspect_err(|_| log::error!("$BOT_OWNER_ID should be a Discord user ID."))
+        .ok()
+}
+
+/// Returns whether `discord_id` is the bot's owner, who's exempt from cooldowns and mutes.
+fn is_owner(discord_id: u64) -> bool {
+    getenv_owner_id() == Some(discord_id)
+}
+
+/// Get the default command prefix from the environment (.env file), for guilds that haven't set
+/// their own.
+///
+/// Falls back to `$` if $BOT_CALL_TOKEN is not defined.
+pub fn getenv_default_prefix() -> String {
+    match std::env::var("BOT_CALL_TOKEN") {
+        Ok(prefix) if !prefix.trim().is_empty() => String::from(prefix.trim()),
+        _ => String::from(DEFAULT_PREFIX),
+    }
+}
+
+/// The command prefix of a guild (or of DMs, if `guild_id` is None).
+pub fn guild_prefix(store: &dyn Store, guild_id: Option<u64>) -> Result<String> {
+    let prefix = match guild_id {
+        Some(guild_id) => store.query_guild_settings(guild_id)?.prefix,
+        None => None,
+    };
 
-    let token = env_token.chars().next().expect("BOT_CALL_TOKEN is empty.");
-    if env_token.len() > 1 {
-        log::warn!(
-            "$BOT_CALL_TOKEN not a single character. Truncating to {}",
-            token
-        );
+    Ok(prefix.unwrap_or_else(getenv_default_prefix))
+}
+
+/// Describes when (and where) a guild's recaps are posted.
+fn recap_schedule(settings: &GuildSettings) -> String {
+    const DAYS: [&str; 7] =
+        ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+    let hour = settings.recap_hour;
+    let day = DAYS.get(settings.recap_day as usize).unwrap_or(&DAYS[0]);
+
+    let mut output = match (settings.daily_recap, settings.weekly_recap) {
+        (false, false) => return String::from("No recaps are posted here."),
+        (true, false) => format!("Daily recaps are posted at {hour:02}:00 UTC"),
+        (false, true) => format!("Weekly recaps are posted on {day}s at {hour:02}:00 UTC"),
+        (true, true) => format!("Daily recaps are posted at {hour:02}:00 UTC, and weekly recaps \
+                                 on {day}s"),
+    };
+
+    output += &match settings.channel(AnnouncementKind::Recaps) {
+        Some(channel) => format!(", in <#{channel}>."),
+        None => String::from(", but there's no channel to post them in (see `setchannel`)."),
+    };
+    output
+}
+
+/// e.g. `90` as `1m30s`.
+fn format_seconds(seconds: u64) -> String {
+    match (seconds / 60, seconds % 60) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, 0) => format!("{minutes}m"),
+        (minutes, seconds) => format!("{minutes}m{seconds}s"),
+    }
+}
+
+/// Lists where each kind of announcement is posted in a guild.
+fn announcement_channels(settings: &GuildSettings) -> String {
+    let mut output = String::from("**Announcement channels:**\n");
+    for kind in AnnouncementKind::ALL {
+        let channel = match (settings.channel_override(kind), settings.announcements_channel) {
+            (Some(channel), _) => format!("<#{channel}>"),
+            (None, Some(channel)) => format!("<#{channel}> (default)"),
+            (None, None) => String::from("not announced"),
+        };
+        output += &format!("\t{}: {channel}\n", kind.description());
     }
 
-    token
+    output
 }
 
-/// Returns whether we are in debug mode.
+/// Describes when (and where) a member is reminded about their streak.
+fn describe_reminder(reminder: &StreakReminder) -> String {
+    let place = match reminder.channel_id {
+        Some(channel) => format!("with a ping in <#{channel}>"),
+        None => String::from("by DM"),
+    };
+
+    format!("If your streak is at risk, you're reminded at {:02}:00 UTC ({} hours before \
+             streaks are checked), {place}.", reminder.hour(), reminder.hours_before)
+}
+
+/// Problem list names are short, and made of letters, digits, `-` and `_`.
+fn is_valid_list_name(name: &str) -> bool {
+    name.len() <= MAX_LIST_NAME_LENGTH
+        && !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Lists a guild's problem lists, or how to import one if it has none.
+fn list_names(store: &dyn Store, guild_id: u64, prefix: &str) -> String {
+    match store.query_problem_list_names(guild_id) {
+        Ok(names) if !names.is_empty() => {
+            names.iter().map(|name| format!("`{name}`")).join(", ")
+        }
+        _ => format!("none yet (see `{prefix}importlist`)."),
+    }
+}
+
+/// Returns whether we are in debug mode ($BOT_DEBUG is set to `true` or `1`).
 pub fn is_debug_mode() -> bool {
-    getenv_call_token() == '!'
-}
\ No newline at end of file
+    std::env::var("BOT_DEBUG").is_ok_and(|debug| matches!(debug.trim(), "true" | "1"))
+}
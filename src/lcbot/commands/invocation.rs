@@ -0,0 +1,145 @@
+use crate::lcbot::response::Response;
+
+use anyhow::{Context, Result};
+use serenity::builder::{CreateActionRow, CreateEmbed, CreateMessage, EditInteractionResponse};
+use serenity::model::application::CommandInteraction;
+use serenity::model::channel::{Message, ReactionType};
+use serenity::model::guild::Member;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::model::user::User;
+
+/// Where a command was run from: a prefix message (e.g. `$audit`), or a slash command.
+#[derive(Clone, Copy)]
+pub enum Invocation<'a> {
+    /// `prefix` is the guild's prefix, even if the bot was mentioned instead.
+    Message { msg: &'a Message, prefix: &'a str },
+    Slash(&'a CommandInteraction),
+}
+
+impl Invocation<'_> {
+    /// What commands start with, as shown in usage strings and hints.
+    pub fn prefix(&self) -> &str {
+        match self {
+            Invocation::Message { prefix, .. } => prefix,
+            Invocation::Slash(_) => "/",
+        }
+    }
+
+    pub fn author(&self) -> &User {
+        match self {
+            Invocation::Message { msg, .. } => &msg.author,
+            Invocation::Slash(command) => &command.user,
+        }
+    }
+
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            Invocation::Message { msg, .. } => msg.guild_id,
+            Invocation::Slash(command) => command.guild_id,
+        }
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            Invocation::Message { msg, .. } => msg.channel_id,
+            Invocation::Slash(command) => command.channel_id,
+        }
+    }
+
+    /// The URL of the first file attached to the command's message, if any.
+    ///
+    /// Slash commands take attachments as options instead (see `ArgKind::Attachment`).
+    pub fn attachment_url(&self) -> Option<&str> {
+        match self {
+            Invocation::Message { msg, .. } => {
+                msg.attachments.first().map(|file| file.url.as_str())
+            }
+            Invocation::Slash(_) => None,
+        }
+    }
+
+    /// The author, as a member of the guild the command was run in.
+    pub async fn member(&self, ctx: &serenity::client::Context) -> Result<Member> {
+        match self {
+            Invocation::Message { msg, .. } => Ok(msg.member(ctx).await?),
+            Invocation::Slash(command) => command
+                .member
+                .as_deref()
+                .cloned()
+                .context("This command can only be used in a server."),
+        }
+    }
+
+    /// Acknowledges a command that has nothing else to say.
+    ///
+    /// Messages get a ✅ reaction (and no reply); slash commands always need a reply, so that's
+    /// what it is.
+    pub async fn react_ok(&self, ctx: &serenity::client::Context) -> Result<String> {
+        match self {
+            Invocation::Message { msg, .. } => {
+                msg.react(&ctx.http, ReactionType::Unicode(String::from("✅"))).await?;
+                Ok(String::from(""))
+            }
+            Invocation::Slash(_) => Ok(String::from("✅")),
+        }
+    }
+
+    /// Replies with `content` and some `components` (e.g. buttons) under it.
+    ///
+    /// The reply is sent here and now, so the command's own reply should be left empty.
+    pub async fn reply_with_components(
+        &self,
+        ctx: &serenity::client::Context,
+        content: String,
+        components: Vec<CreateActionRow>) -> Result<()>
+    {
+        match self {
+            Invocation::Message { msg, .. } => {
+                msg.channel_id
+                    .send_message(&ctx.http, CreateMessage::new()
+                        .content(content)
+                        .components(components))
+                    .await?;
+            }
+            Invocation::Slash(command) => {
+                command
+                    .edit_response(&ctx.http, EditInteractionResponse::new()
+                        .content(content)
+                        .components(components))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replies with `embed`, or with `text` if the embed can't be sent (e.g. the bot may not
+    /// embed links in the channel). Like any other reply, long text is sent a page at a time.
+    ///
+    /// The reply is sent here and now, so the command's own reply should be left empty.
+    pub async fn reply_with_embed(
+        &self,
+        ctx: &serenity::client::Context,
+        embed: CreateEmbed,
+        text: String) -> Result<()>
+    {
+        let sent = match self {
+            Invocation::Message { msg, .. } => msg.channel_id
+                .send_message(&ctx.http, CreateMessage::new().embed(embed))
+                .await
+                .map(|_| ()),
+            Invocation::Slash(command) => command
+                .edit_response(&ctx.http, EditInteractionResponse::new().embed(embed))
+                .await
+                .map(|_| ()),
+        };
+
+        if let Err(err) = sent {
+            log::warn!("[reply_with_embed] Couldn't send embed, sending text: {err}");
+            let (content, components) = Response::new(&text).first_page();
+            self.reply_with_components(ctx, content, components).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,558 @@
+use super::registry::{ArgKind, CLEAR_OPTION, CommandSpec, OptionSpec};
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{NaiveDate, TimeDelta, Utc};
+use serenity::model::application::{ResolvedOption, ResolvedValue};
+
+/// A value parsed out of a command's arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    /// A Discord user, from an @mention.
+    Member(u64),
+    /// A Discord role, from a role mention; `none` to explicitly ask for no role.
+    Role(Option<u64>),
+    /// A Discord channel, from a channel mention; `none` to explicitly ask for no channel.
+    Channel(Option<u64>),
+    Bool(bool),
+    Integer(u64),
+    Duration(TimeDelta),
+    Date(NaiveDate),
+    /// The URL of an attached file.
+    Attachment(String),
+}
+
+/// A command's arguments, checked against its `CommandSpec`.
+#[derive(Debug, Default)]
+pub struct Args {
+    /// (name, value) of every positional argument that was given.
+    positionals: Vec<(&'static str, Value)>,
+    /// (name, value) of every option that was given, in order.
+    options: Vec<(&'static str, Value)>,
+}
+
+impl Args {
+    /// The argument or option `name`, if it was given. Options given more than once are their
+    /// last value.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.positionals
+            .iter()
+            .chain(self.options.iter().rev())
+            .find(|(arg, _)| *arg == name)
+            .map(|(_, value)| value)
+    }
+
+    /// The argument `name` as text, if it was given and is text.
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Every option that was given, in the order they were given.
+    pub fn options(&self) -> &[(&'static str, Value)] {
+        &self.options
+    }
+}
+
+/// A piece of a command's input. Quoted tokens are always taken literally.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+/// Splits a command's input into tokens: on whitespace, except inside "double" or 'single' quotes.
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if c == '"' || c == '\'' {
+            chars.next();
+            let mut text = String::new();
+            let mut closed = false;
+            for next in chars.by_ref() {
+                if next == c {
+                    closed = true;
+                    break;
+                }
+                text.push(next);
+            }
+
+            if !closed {
+                bail!("Unterminated quote in `{c}{text}`.");
+            }
+            tokens.push(Token { text, quoted: true });
+        } else {
+            let mut text = String::new();
+            while let Some(&next) = chars.peek() {
+                if next.is_whitespace() {
+                    break;
+                }
+                text.push(next);
+                chars.next();
+            }
+            tokens.push(Token { text, quoted: false });
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses `input` (everything after the command's name) into `spec`'s arguments.
+/// Errors point at the offending token, but leave it to the caller to show the command's usage.
+///
+/// Options can be given as `key=value` (whitespace around the `=` is fine) or `--key value`;
+/// everything else fills positional arguments in order.
+pub fn parse(spec: &CommandSpec, input: &str) -> Result<Args> {
+    let mut tokens = tokenize(input)?.into_iter().peekable();
+    let mut args = Args::default();
+    let mut words = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        if token.quoted {
+            words.push(token.text);
+            continue;
+        }
+
+        // --key [value]
+        if let Some(key) = token.text.strip_prefix("--") {
+            let option = find_option(spec, key, &token.text)?;
+            let value = match tokens.peek() {
+                // Bool flags don't need a value.
+                Some(next) if option.kind == ArgKind::Bool
+                    && parse_value(ArgKind::Bool, &next.text).is_err() => String::from("true"),
+                None if option.kind == ArgKind::Bool => String::from("true"),
+                Some(_) => tokens.next().map(|next| next.text).unwrap_or_default(),
+                None => bail!("Expected a value after `{}`.", token.text),
+            };
+            args.options.push((option.name, parse_value(option.kind, &value)?));
+            continue;
+        }
+
+        // key=value, key= value, key =value, key = value
+        let glued = match token.text.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Some((key.to_owned(), value.to_owned())),
+            _ if tokens.peek().is_some_and(|next| !next.quoted && next.text.starts_with('=')) => {
+                let next = tokens.next().unwrap_or_else(|| unreachable!());
+                Some((token.text.clone(), next.text[1..].to_owned()))
+            }
+            _ => None,
+        };
+        if let Some((key, mut value)) = glued {
+            if value.is_empty() {
+                value = tokens
+                    .next()
+                    .map(|next| next.text)
+                    .ok_or_else(|| anyhow!("Expected a value after `{key}=`."))?;
+            }
+            let option = find_option(spec, &key, &format!("{key}={value}"))?;
+            args.options.push((option.name, parse_value(option.kind, &value)?));
+            continue;
+        }
+
+        words.push(token.text);
+    }
+
+    bind_positionals(spec, words, &mut args)?;
+    apply_clear(spec, &mut args);
+
+    Ok(args)
+}
+
+/// Reads a slash command's options into `spec`'s arguments.
+///
+/// Discord already makes sure required options are there and that booleans, integers, roles,
+/// channels and attachments are well-formed; everything else comes in as a string, and is parsed
+/// like it would be from a message.
+pub fn parse_slash(spec: &CommandSpec, options: &[ResolvedOption]) -> Result<Args> {
+    let mut args = Args::default();
+
+    for option in options {
+        let (name, kind) = spec
+            .find_slash_option(option.name)
+            .ok_or_else(|| anyhow!("Unknown option `{}`.", option.name))?;
+
+        let value = match option.value {
+            ResolvedValue::Boolean(state) => Value::Bool(state),
+            ResolvedValue::Integer(number) => parse_value(kind, &number.to_string())?,
+            ResolvedValue::Role(role) => Value::Role(Some(role.id.get())),
+            ResolvedValue::Channel(channel) => Value::Channel(Some(channel.id.get())),
+            ResolvedValue::Attachment(attachment) => Value::Attachment(attachment.url.clone()),
+            ResolvedValue::String(text) => parse_value(kind, text)?,
+            _ => bail!("Unexpected value for `{}`.", option.name),
+        };
+
+        if spec.args.iter().any(|arg| arg.name == name) {
+            args.positionals.push((name, value));
+        } else {
+            args.options.push((name, value));
+        }
+    }
+
+    apply_clear(spec, &mut args);
+
+    Ok(args)
+}
+
+/// Turns a `clear` flag into `none` for `spec`'s role or channel argument, replacing any that was
+/// given. Slash commands have no other way to pass `none`.
+fn apply_clear(spec: &CommandSpec, args: &mut Args) {
+    if args.get(CLEAR_OPTION.name) != Some(&Value::Bool(true)) {
+        return;
+    }
+
+    let cleared = spec.args.iter().find_map(|arg| match arg.kind {
+        ArgKind::Role => Some((arg.name, Value::Role(None))),
+        ArgKind::Channel => Some((arg.name, Value::Channel(None))),
+        _ => None,
+    });
+    let Some((name, none)) = cleared else {
+        return;
+    };
+
+    args.positionals.retain(|(arg, _)| *arg != name);
+    args.positionals.push((name, none));
+}
+
+fn find_option(spec: &CommandSpec, key: &str, token: &str) -> Result<&'static OptionSpec> {
+    spec.options
+        .iter()
+        .find(|option| option.name == key)
+        .ok_or_else(|| anyhow!("Unknown option `{key}` in `{token}`."))
+}
+
+/// Hands out `words` to `spec`'s positional arguments, left to right.
+///
+/// Optional arguments only get a word if there are enough left over for the required ones after
+/// them; a variadic (last) argument takes everything that's left, joined with spaces.
+fn bind_positionals(spec: &CommandSpec, words: Vec<String>, args: &mut Args) -> Result<()> {
+    let mut words = words.into_iter().collect::<std::collections::VecDeque<_>>();
+
+    for (i, arg) in spec.args.iter().enumerate() {
+        let required_after = spec.args[i + 1..].iter().filter(|arg| arg.required).count();
+        let available = words.len().saturating_sub(required_after);
+
+        if available == 0 {
+            if arg.required {
+                bail!("Missing `{}`.", arg.name);
+            }
+            continue;
+        }
+
+        let word = if arg.variadic {
+            words.drain(..available).collect::<Vec<_>>().join(" ")
+        } else {
+            words.pop_front().unwrap_or_default()
+        };
+
+        args.positionals.push((arg.name, parse_value(arg.kind, &word)?));
+    }
+
+    match words.front() {
+        Some(extra) => bail!("Unexpected `{extra}`."),
+        None => Ok(()),
+    }
+}
+
+/// Parses a single token as a value of `kind`.
+pub fn parse_value(kind: ArgKind, token: &str) -> Result<Value> {
+    match kind {
+        ArgKind::Text | ArgKind::Problem => Ok(Value::Text(token.to_owned())),
+        ArgKind::Choice(choices) => {
+            if choices.contains(&token) {
+                Ok(Value::Text(token.to_owned()))
+            } else {
+                bail!("Expected one of {}, got `{token}`.",
+                      choices.iter().map(|c| format!("`{c}`")).collect::<Vec<_>>().join(", "))
+            }
+        }
+        ArgKind::User => Ok(parse_mention(token)
+            .map(Value::Member)
+            .unwrap_or_else(|| Value::Text(token.to_owned()))),
+        ArgKind::Role => match token {
+            "none" => Ok(Value::Role(None)),
+            _ => parse_role_mention(token)
+                .map(|role| Value::Role(Some(role)))
+                .ok_or_else(|| anyhow!("Expected a role mention or `none`, got `{token}`.")),
+        },
+        ArgKind::Channel => match token {
+            "none" => Ok(Value::Channel(None)),
+            _ => parse_channel_mention(token)
+                .map(|channel| Value::Channel(Some(channel)))
+                .ok_or_else(|| anyhow!("Expected a channel mention or `none`, got `{token}`.")),
+        },
+        ArgKind::Bool => match token {
+            "true" | "yes" | "on" => Ok(Value::Bool(true)),
+            "false" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => bail!("Expected `true` or `false`, got `{token}`."),
+        },
+        ArgKind::Integer { min, max } => token
+            .parse::<u64>()
+            .ok()
+            .filter(|number| (min..=max).contains(number))
+            .map(Value::Integer)
+            .ok_or_else(|| anyhow!("Expected a whole number from {min} to {max}, got `{token}`.")),
+        ArgKind::Duration => parse_duration(token)
+            .map(Value::Duration)
+            .ok_or_else(|| anyhow!("Expected a duration like `30m`, `12h` or `7d`, got `{token}`.")),
+        ArgKind::Date => parse_date(token)
+            .map(Value::Date)
+            .ok_or_else(|| anyhow!("Expected a date like `2025-06-01`, `today` or `tomorrow`, \
+                                    got `{token}`.")),
+        ArgKind::Attachment => bail!("Attach the file to your message instead of `{token}`."),
+    }
+}
+
+/// Parses a Discord user mention (`<@id>` or `<@!id>`) into the mentioned user's ID.
+pub fn parse_mention(s: &str) -> Option<u64> {
+    s.strip_prefix("<@")?
+        .strip_suffix('>')?
+        .trim_start_matches('!')
+        .parse()
+        .ok()
+}
+
+/// Parses a Discord role mention (`<@&id>`) into the mentioned role's ID.
+fn parse_role_mention(s: &str) -> Option<u64> {
+    s.strip_prefix("<@&")?.strip_suffix('>')?.parse().ok()
+}
+
+/// Parses a Discord channel mention (`<#id>`) into the mentioned channel's ID.
+fn parse_channel_mention(s: &str) -> Option<u64> {
+    s.strip_prefix("<#")?.strip_suffix('>')?.parse().ok()
+}
+
+/// Parses durations like `45s`, `30m`, `12h`, `7d`, `2w`, or combinations like `1d12h`.
+fn parse_duration(s: &str) -> Option<TimeDelta> {
+    let mut total = TimeDelta::zero();
+    let mut digits = String::new();
+
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+
+        let amount: i64 = std::mem::take(&mut digits).parse().ok()?;
+        let delta = match c {
+            's' => TimeDelta::try_seconds(amount)?,
+            'm' => TimeDelta::try_minutes(amount)?,
+            'h' => TimeDelta::try_hours(amount)?,
+            'd' => TimeDelta::try_days(amount)?,
+            'w' => TimeDelta::try_weeks(amount)?,
+            _ => return None,
+        };
+        // Each unit is in range, but their sum may not be.
+        total = total.checked_add(&delta)?;
+    }
+
+    (digits.is_empty() && total > TimeDelta::zero()).then_some(total)
+}
+
+/// Parses dates like `2025-06-01`, `today` or `tomorrow` (in UTC).
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    let today = Utc::now().date_naive();
+    match s {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        _ => NaiveDate::parse_from_str(s, "%Y-%m-%d").ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcbot::commands::permissions::Permission;
+    use crate::lcbot::commands::registry::{ArgSpec, CommandKind};
+
+    const fn arg(name: &'static str, kind: ArgKind, required: bool, variadic: bool) -> ArgSpec {
+        ArgSpec { name, kind, description: "", required, variadic }
+    }
+
+    const fn spec(args: &'static [ArgSpec], options: &'static [OptionSpec]) -> CommandSpec {
+        CommandSpec {
+            kind: CommandKind::Help,
+            name: "test",
+            aliases: &[],
+            args,
+            options,
+            permission: Permission::Everyone,
+            description: "",
+            ephemeral: false,
+            cooldown: 0,
+        }
+    }
+
+    // test <user> [count] <problem...> [deadline=duration] [--public]
+    const SPEC: CommandSpec = spec(
+        &[
+            arg("user", ArgKind::User, true, false),
+            arg("count", ArgKind::Integer { min: 1, max: 10 }, false, false),
+            arg("problem", ArgKind::Problem, true, true),
+        ],
+        &[
+            OptionSpec { name: "deadline", kind: ArgKind::Duration, description: "" },
+            OptionSpec { name: "public", kind: ArgKind::Bool, description: "" },
+        ],
+    );
+
+    fn texts(input: &str) -> Vec<(String, bool)> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| (token.text, token.quoted))
+            .collect()
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace_outside_quotes() {
+        assert_eq!(texts("  a \"b c\"  'd \"e\"' f"), vec![
+            (String::from("a"), false),
+            (String::from("b c"), true),
+            (String::from("d \"e\""), true),
+            (String::from("f"), false),
+        ]);
+        assert_eq!(texts("\"\""), vec![(String::new(), true)]);
+        assert!(texts("").is_empty());
+    }
+
+    #[test]
+    fn tokenize_rejects_unterminated_quotes() {
+        let err = tokenize("a \"b c").unwrap_err().to_string();
+        assert_eq!(err, "Unterminated quote in `\"b c`.");
+    }
+
+    #[test]
+    fn bind_positionals_skips_optionals_only_when_needed() {
+        let mut args = Args::default();
+        bind_positionals(&SPEC, words(&["alice", "sum"]), &mut args).unwrap();
+        assert_eq!(args.text("user"), Some("alice"));
+        assert_eq!(args.get("count"), None);
+        assert_eq!(args.text("problem"), Some("sum"));
+
+        let mut args = Args::default();
+        bind_positionals(&SPEC, words(&["alice", "3", "two", "sum"]), &mut args).unwrap();
+        assert_eq!(args.get("count"), Some(&Value::Integer(3)));
+        assert_eq!(args.text("problem"), Some("two sum"));
+
+        let mut args = Args::default();
+        bind_positionals(&SPEC, words(&["<@!42>", "3"]), &mut args).unwrap();
+        assert_eq!(args.get("user"), Some(&Value::Member(42)));
+        assert_eq!(args.get("count"), None);
+        assert_eq!(args.text("problem"), Some("3"));
+    }
+
+    #[test]
+    fn bind_positionals_reports_bad_missing_and_extra_words() {
+        // Optional arguments are filled by position, not by whether the word fits.
+        let err = bind_positionals(&SPEC, words(&["alice", "two", "sum"]), &mut Args::default())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Expected a whole number from 1 to 10, got `two`.");
+
+        let err = bind_positionals(&SPEC, Vec::new(), &mut Args::default()).unwrap_err();
+        assert_eq!(err.to_string(), "Missing `user`.");
+
+        const SHORT: CommandSpec = spec(&[arg("user", ArgKind::User, true, false)], &[]);
+        let err = bind_positionals(&SHORT, words(&["alice", "bob"]), &mut Args::default())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected `bob`.");
+    }
+
+    #[test]
+    fn parse_reads_options_in_every_form() {
+        for input in ["alice sum deadline=1d", "alice sum deadline= 1d", "alice sum deadline =1d",
+                      "alice sum deadline = 1d", "alice sum --deadline 1d"] {
+            let args = parse(&SPEC, input).unwrap();
+            assert_eq!(args.options(), &[("deadline", Value::Duration(TimeDelta::days(1)))],
+                       "{input}");
+            assert_eq!(args.text("problem"), Some("sum"), "{input}");
+        }
+
+        let args = parse(&SPEC, "alice --public \"deadline=1d\"").unwrap();
+        assert_eq!(args.options(), &[("public", Value::Bool(true))]);
+        assert_eq!(args.text("problem"), Some("deadline=1d"));
+
+        let args = parse(&SPEC, "alice --public no sum").unwrap();
+        assert_eq!(args.options(), &[("public", Value::Bool(false))]);
+    }
+
+    #[test]
+    fn get_finds_options_by_name() {
+        let args = parse(&SPEC, "alice sum deadline=1d --public deadline=2d").unwrap();
+        assert_eq!(args.get("deadline"), Some(&Value::Duration(TimeDelta::days(2))));
+        assert_eq!(args.get("public"), Some(&Value::Bool(true)));
+        assert_eq!(args.get("user"), Some(&Value::Text(String::from("alice"))));
+
+        let args = parse(&SPEC, "alice sum --public").unwrap();
+        assert_eq!(args.get("deadline"), None);
+    }
+
+    #[test]
+    fn clear_stands_in_for_none() {
+        const ROLE: CommandSpec = spec(&[arg("@role|none", ArgKind::Role, false, false)],
+                                       &[CLEAR_OPTION]);
+
+        // What `parse_slash` reads out of `/adminrole role:@mods clear:true`.
+        let mut args = Args {
+            positionals: vec![("@role|none", Value::Role(Some(7)))],
+            options: vec![("clear", Value::Bool(true))],
+        };
+        apply_clear(&ROLE, &mut args);
+        assert_eq!(args.get("@role|none"), Some(&Value::Role(None)));
+
+        let args = parse(&ROLE, "--clear").unwrap();
+        assert_eq!(args.get("@role|none"), Some(&Value::Role(None)));
+
+        let args = parse(&ROLE, "<@&7> clear=false").unwrap();
+        assert_eq!(args.get("@role|none"), Some(&Value::Role(Some(7))));
+
+        const CHANNEL: CommandSpec = spec(&[arg("#channel|none", ArgKind::Channel, false, false)],
+                                          &[CLEAR_OPTION]);
+        let args = parse(&CHANNEL, "--clear").unwrap();
+        assert_eq!(args.get("#channel|none"), Some(&Value::Channel(None)));
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_bad_token() {
+        let err = |input| parse(&SPEC, input).unwrap_err().to_string();
+        assert_eq!(err("alice sum deadline=soon"),
+                   "Expected a duration like `30m`, `12h` or `7d`, got `soon`.");
+        assert_eq!(err("alice sum --deadline 15000000000w15000000000w"),
+                   "Expected a duration like `30m`, `12h` or `7d`, got \
+                    `15000000000w15000000000w`.");
+        assert_eq!(err("alice sum color=red"), "Unknown option `color` in `color=red`.");
+        assert_eq!(err("alice sum --deadline"), "Expected a value after `--deadline`.");
+        assert_eq!(err("alice 11 sum"),
+                   "Expected a whole number from 1 to 10, got `11`.");
+    }
+
+    #[test]
+    fn parse_duration_edge_cases() {
+        assert_eq!(parse_duration("1d12h"), Some(TimeDelta::hours(36)));
+        assert_eq!(parse_duration("2w"), Some(TimeDelta::days(14)));
+        assert_eq!(parse_duration("90s"), Some(TimeDelta::seconds(90)));
+        assert_eq!(parse_duration("7"), None);
+        assert_eq!(parse_duration("1d7"), None);
+        assert_eq!(parse_duration("0s"), None);
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration("1y"), None);
+        // Either half fits in a TimeDelta, but not both.
+        assert!(parse_duration("15000000000w").is_some());
+        assert_eq!(parse_duration("15000000000w15000000000w"), None);
+        assert_eq!(parse_duration("16000000000w"), None);
+        assert_eq!(parse_duration("99999999999999999999s"), None);
+    }
+}
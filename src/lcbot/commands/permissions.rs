@@ -0,0 +1,94 @@
+use crate::lcdb::Store;
+
+use super::invocation::Invocation;
+use super::getenv_owner_id;
+
+use anyhow::{Result, anyhow};
+use serenity::model::Permissions;
+
+/// Who is allowed to run a command. Levels are ordered: each one includes the ones below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    /// Anyone.
+    Everyone,
+    /// Anyone, as long as the LeetCode account being acted on is their own (verified) one.
+    /// Admins may act on any account.
+    SelfOnly,
+    /// Members who can manage the server, or who have the guild's admin role.
+    Admin,
+    /// The bot's owner ($BOT_OWNER_ID).
+    Owner,
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Permission::Everyone => "anyone",
+            Permission::SelfOnly => "the verified owner of the account, or server admins",
+            Permission::Admin => "server admins",
+            Permission::Owner => "the bot's owner",
+        })
+    }
+}
+
+impl Permission {
+    /// The highest permission level held by whoever ran a command.
+    pub async fn of_author(
+        ctx: &serenity::client::Context,
+        invocation: &Invocation<'_>,
+        store: &dyn Store) -> Result<Permission>
+    {
+        if getenv_owner_id() == Some(invocation.author().id.get()) {
+            return Ok(Permission::Owner);
+        }
+
+        let Some(guild_id) = invocation.guild_id() else {
+            return Ok(Permission::Everyone);
+        };
+
+        let member = invocation.member(ctx).await?;
+        let guild = guild_id.to_partial_guild(ctx).await?;
+        let admin_role = store.query_guild_settings(guild_id.get())?.admin_role;
+
+        let manages_guild = guild
+            .member_permissions(&member)
+            .intersects(Permissions::ADMINISTRATOR | Permissions::MANAGE_GUILD);
+        let has_admin_role = admin_role
+            .is_some_and(|admin_role| member.roles.iter().any(|role| role.get() == admin_role));
+
+        Ok(if manages_guild || has_admin_role { Permission::Admin } else { Permission::Everyone })
+    }
+
+    /// Checks that a `caller` may run `command`, which requires this permission level.
+    ///
+    /// `SelfOnly` always passes here: only the command itself knows which account it acts on, so it
+    /// has to call `check_self` once it does.
+    pub fn check(self, caller: Permission, command: &str) -> Result<()> {
+        match self {
+            Permission::Owner if caller < Permission::Owner => {
+                Err(anyhow!("⛔ `{command}` can only be used by the bot's owner."))
+            }
+            Permission::Admin if caller < Permission::Admin => {
+                Err(anyhow!("⛔ `{command}` can only be used by server admins."))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Checks that a `caller` (with Discord ID `discord_id`) may act on the LeetCode account
+/// `username`: it has to be their own verified account, unless they're an admin.
+pub fn check_self(
+    store: &dyn Store,
+    discord_id: u64,
+    caller: Permission,
+    username: &str,
+    prefix: &str) -> Result<()>
+{
+    if caller >= Permission::Admin || store.is_verified_owner(discord_id, username)? {
+        return Ok(());
+    }
+
+    Err(anyhow!("⛔ Only the verified owner of {username} or a server admin can do that \
+                 (see `{prefix}verify`)."))
+}
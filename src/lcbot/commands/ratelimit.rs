@@ -0,0 +1,119 @@
+use super::registry::CommandSpec;
+
+use anyhow::{Result, anyhow};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+// At most 8 commands run at once; the rest wait their turn for up to 10 seconds.
+const MAX_CONCURRENT_COMMANDS: usize = 8;
+const CONCURRENCY_WAIT: Duration = Duration::from_secs(10);
+
+// 5 invalid commands within a minute get a member ignored for 5 minutes.
+const MAX_STRIKES: usize = 5;
+const STRIKE_WINDOW: Duration = Duration::from_secs(60);
+const MUTE_DURATION: Duration = Duration::from_secs(300);
+
+/// Keeps members from running commands faster than the bot (and LeetCode) can keep up with.
+///
+/// Nothing here is persisted: a restart forgives everyone.
+pub static LIMITER: LazyLock<Limiter> = LazyLock::new(Limiter::default);
+
+pub struct Limiter {
+    state: Mutex<LimiterState>,
+    running: Semaphore,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    /// (Discord ID, command name) -> when the command is off cooldown for them
+    ready_at: HashMap<(u64, &'static str), Instant>,
+    /// Discord ID -> when they sent each of their recent invalid commands
+    strikes: HashMap<u64, Vec<Instant>>,
+    /// Discord ID -> when they're heard again
+    muted_until: HashMap<u64, Instant>,
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self {
+            state: Mutex::default(),
+            running: Semaphore::new(MAX_CONCURRENT_COMMANDS),
+        }
+    }
+}
+
+impl Limiter {
+    fn state(&self) -> Result<MutexGuard<'_, LimiterState>> {
+        self.state.lock().map_err(|_| anyhow!("Limiter lock was poisoned."))
+    }
+
+    /// Fails if `discord_id` is muted, saying for how long.
+    pub fn check_muted(&self, discord_id: u64) -> Result<()> {
+        let mut state = self.state()?;
+        let now = Instant::now();
+        state.muted_until.retain(|_, until| *until > now);
+
+        match state.muted_until.get(&discord_id) {
+            Some(until) => Err(anyhow!("🔇 You've sent too many invalid commands; try again in \
+                                        {}s.", seconds_left(*until, now))),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts an invalid command against `discord_id`.
+    /// Returns `true` if that was one too many, and they're now muted.
+    pub fn strike(&self, discord_id: u64) -> Result<bool> {
+        let mut state = self.state()?;
+        let now = Instant::now();
+
+        let strikes = state.strikes.entry(discord_id).or_default();
+        strikes.retain(|strike| now.duration_since(*strike) < STRIKE_WINDOW);
+        strikes.push(now);
+        if strikes.len() < MAX_STRIKES {
+            return Ok(false);
+        }
+
+        state.strikes.remove(&discord_id);
+        state.muted_until.insert(discord_id, now + MUTE_DURATION);
+        log::warn!("Muted {discord_id} for {}s after {MAX_STRIKES} invalid commands.",
+                   MUTE_DURATION.as_secs());
+
+        Ok(true)
+    }
+
+    /// Fails if `discord_id` ran `spec` too recently, saying when they can run it again.
+    /// Otherwise, starts its cooldown.
+    pub fn check_cooldown(&self, discord_id: u64, spec: &CommandSpec) -> Result<()> {
+        let mut state = self.state()?;
+        let now = Instant::now();
+        state.ready_at.retain(|_, ready_at| *ready_at > now);
+
+        if let Some(ready_at) = state.ready_at.get(&(discord_id, spec.name)) {
+            return Err(anyhow!("⏳ `{}` is on cooldown; try again in {}s.",
+                               spec.name, seconds_left(*ready_at, now)));
+        }
+
+        if spec.cooldown > 0 {
+            let cooldown = Duration::from_secs(spec.cooldown);
+            state.ready_at.insert((discord_id, spec.name), now + cooldown);
+        }
+
+        Ok(())
+    }
+
+    /// Waits for a free slot to run a command in. The slot is given back once the permit drops.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>> {
+        tokio::time::timeout(CONCURRENCY_WAIT, self.running.acquire())
+            .await
+            .map_err(|_| anyhow!("⏳ I'm swamped right now; try again in a few seconds."))?
+            .map_err(|_| anyhow!("Commands are no longer being run."))
+    }
+}
+
+/// Whole seconds from `now` until `until`, rounded up so that it never says 0s.
+fn seconds_left(until: Instant, now: Instant) -> u64 {
+    until.duration_since(now).as_millis().div_ceil(1000) as u64
+}
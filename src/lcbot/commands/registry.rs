@@ -0,0 +1,856 @@
+use super::permissions::Permission;
+use super::is_debug_mode;
+
+use itertools::Itertools;
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::application::CommandOptionType;
+
+// Discord's limit on the length of slash command (and option) descriptions.
+const MAX_SLASH_DESCRIPTION_LENGTH: usize = 100;
+
+/// Every command the bot knows. Dispatch, usage errors and help are all generated from this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandKind {
+    Audit,
+    Recent,
+    Link,
+    Verify,
+    Tracklist,
+    Track,
+    Untrack,
+    Prefs,
+    AdminRole,
+    SetChannel,
+    Digest,
+    Recap,
+    RecapSchedule,
+    Leaderboard,
+    PinLeaderboard,
+    Points,
+    Season,
+    Seasons,
+    Compare,
+    Challenge,
+    Challenges,
+    Goal,
+    SetGoal,
+    ImportList,
+    List,
+    Follow,
+    Unfollow,
+    Reminder,
+    Freezes,
+    Kudos,
+    Help,
+    Prefix,
+    Clanker,
+    Insert,
+}
+
+/// What kind of value an argument takes. See `parser::parse_value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    /// Any word, or a quoted phrase.
+    Text,
+    /// One of a fixed set of words.
+    Choice(&'static [&'static str]),
+    /// A leetcode username, or an @mention of a member.
+    User,
+    /// A problem's title.
+    Problem,
+    /// A role mention, or `none`.
+    Role,
+    /// A channel mention, or `none`.
+    Channel,
+    /// `true` or `false` (or `yes`/`no`, `on`/`off`).
+    Bool,
+    /// A whole number from `min` to `max`.
+    Integer { min: u64, max: u64 },
+    /// e.g. `30m`, `12h`, `7d` or `1d12h`.
+    Duration,
+    /// e.g. `2025-06-01`, `today` or `tomorrow`.
+    Date,
+    /// A file attached to the command. Messages attach it to themselves instead.
+    Attachment,
+}
+
+impl ArgKind {
+    /// How a value of this kind shows up in usage strings.
+    fn hint(&self) -> String {
+        match self {
+            ArgKind::Choice(choices) => choices.join("|"),
+            ArgKind::Bool => String::from("true|false"),
+            ArgKind::Integer { min, max } => format!("{min}-{max}"),
+            ArgKind::Duration => String::from("duration"),
+            ArgKind::Date => String::from("date"),
+            ArgKind::Attachment => String::from("file"),
+            ArgKind::Text | ArgKind::User | ArgKind::Problem | ArgKind::Role
+                | ArgKind::Channel => String::from("value"),
+        }
+    }
+
+    /// The type of slash command option that takes a value of this kind.
+    ///
+    /// Everything but booleans, integers, roles, channels and attachments is taken as a string,
+    /// then parsed like it would be from a message.
+    fn slash_type(&self) -> CommandOptionType {
+        match self {
+            ArgKind::Bool => CommandOptionType::Boolean,
+            ArgKind::Integer { .. } => CommandOptionType::Integer,
+            ArgKind::Role => CommandOptionType::Role,
+            ArgKind::Channel => CommandOptionType::Channel,
+            ArgKind::Attachment => CommandOptionType::Attachment,
+            _ => CommandOptionType::String,
+        }
+    }
+
+    /// A slash command option of this kind.
+    fn slash_option(&self, name: &str, description: &str, required: bool) -> CreateCommandOption {
+        let mut option = CreateCommandOption::new(
+            self.slash_type(), slash_name(name), slash_description(description))
+            .required(required);
+
+        match self {
+            ArgKind::Choice(choices) => {
+                for choice in *choices {
+                    option = option.add_string_choice(*choice, *choice);
+                }
+            }
+            ArgKind::User | ArgKind::Problem => option = option.set_autocomplete(true),
+            ArgKind::Integer { min, max } => option = option.min_int_value(*min).max_int_value(*max),
+            _ => {}
+        }
+
+        option
+    }
+}
+
+/// A positional argument a command takes.
+#[derive(Debug)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub description: &'static str,
+    pub required: bool,
+
+    /// Takes up the rest of the parameters. Only makes sense for the last argument.
+    pub variadic: bool,
+}
+
+impl ArgSpec {
+    const fn required(name: &'static str, kind: ArgKind, description: &'static str) -> Self {
+        Self { name, kind, description, required: true, variadic: false }
+    }
+
+    const fn optional(name: &'static str, kind: ArgKind, description: &'static str) -> Self {
+        Self { name, kind, description, required: false, variadic: false }
+    }
+
+    const fn variadic(self) -> Self {
+        Self { variadic: true, ..self }
+    }
+
+    /// How the argument shows up in usage strings: `<name>` or `[name]`, with `...` if variadic.
+    /// Arguments with a fixed set of choices show those instead of their name.
+    fn usage(&self) -> String {
+        let dots = if self.variadic { "..." } else { "" };
+        let name = match self.kind {
+            ArgKind::Choice(choices) => choices.join("|"),
+            _ => String::from(self.name),
+        };
+
+        match self.required {
+            true => format!("<{name}{dots}>"),
+            false => format!("[{name}{dots}]"),
+        }
+    }
+}
+
+/// An option a command takes, given as `name=value` or `--name value`.
+#[derive(Debug)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub description: &'static str,
+}
+
+impl OptionSpec {
+    /// How the option shows up in usage strings: `[name=hint]`.
+    fn usage(&self) -> String {
+        format!("[{}={}]", self.name, self.kind.hint())
+    }
+}
+
+/// Everything there is to know about a command, short of how to run it.
+#[derive(Debug)]
+pub struct CommandSpec {
+    pub kind: CommandKind,
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub args: &'static [ArgSpec],
+    pub options: &'static [OptionSpec],
+    pub permission: Permission,
+    pub description: &'static str,
+
+    /// Whether slash command replies are only shown to whoever ran the command.
+    pub ephemeral: bool,
+
+    /// Seconds a member has to wait between two uses of the command.
+    pub cooldown: u64,
+}
+
+const USERNAME_ARG: ArgSpec = ArgSpec::optional(
+    "username|@member",
+    ArgKind::User,
+    "A leetcode username, or a member who linked theirs. Defaults to your linked account.");
+
+/// Stands in for a `none` role or channel: slash commands can't pass `none` to those options.
+pub const CLEAR_OPTION: OptionSpec = OptionSpec {
+    name: "clear",
+    kind: ArgKind::Bool,
+    description: "Unset it, same as passing `none`.",
+};
+
+// `all`, then the name of every `models::AnnouncementKind`.
+const ANNOUNCEMENT_KINDS: &[&str] = &["all", "accepted", "failures", "streaks", "updates",
+                                      "recaps", "seasons", "challenges", "goals"];
+
+// The name of every `models::LeaderboardPeriod`.
+const LEADERBOARD_PERIODS: &[&str] = &["day", "week", "month", "all"];
+
+/// Days of the week, as given to `recapschedule`, starting on Monday.
+pub const WEEKDAYS: &[&str] = &["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        kind: CommandKind::Audit,
+        name: "audit",
+        aliases: &["stats"],
+        args: &[USERNAME_ARG],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Get stats on a leetcode user.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Recent,
+        name: "recent",
+        aliases: &["last"],
+        args: &[USERNAME_ARG],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Get the most recent submission from a leetcode user.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Link,
+        name: "link",
+        aliases: &[],
+        args: &[ArgSpec::required("username", ArgKind::Text, "Your leetcode username.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Link your Discord account to your leetcode account.",
+        ephemeral: true,
+        cooldown: 30,
+    },
+    CommandSpec {
+        kind: CommandKind::Verify,
+        name: "verify",
+        aliases: &[],
+        args: &[ArgSpec::optional("username", ArgKind::Text, "The leetcode account to verify. \
+                                                               Leave it out to finish a \
+                                                               verification.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Prove that you own a leetcode account (run it again to finish).",
+        ephemeral: true,
+        cooldown: 30,
+    },
+    CommandSpec {
+        kind: CommandKind::Tracklist,
+        name: "tracklist",
+        aliases: &["tracked"],
+        args: &[],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "List all tracked users.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Track,
+        name: "track",
+        aliases: &[],
+        args: &[USERNAME_ARG],
+        options: &[],
+        permission: Permission::SelfOnly,
+        description: "Track a user. This will cause the bot to begin tracking submissions for \
+                      this user.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Untrack,
+        name: "untrack",
+        aliases: &[],
+        args: &[USERNAME_ARG],
+        options: &[],
+        permission: Permission::SelfOnly,
+        description: "Untrack a user, after confirming. Their history is kept.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Prefs,
+        name: "prefs",
+        aliases: &["preferences"],
+        args: &[USERNAME_ARG],
+        options: &[
+            OptionSpec {
+                name: "announce_fail",
+                kind: ArgKind::Bool,
+                description: "Whether failed submissions are announced.",
+            },
+            OptionSpec {
+                name: "announce_link",
+                kind: ArgKind::Bool,
+                description: "Whether announcements link to the submission.",
+            },
+            OptionSpec {
+                name: "digest",
+                kind: ArgKind::Bool,
+                description: "Whether bursts of submissions are merged into one announcement.",
+            },
+        ],
+        permission: Permission::SelfOnly,
+        description: "Modify announcement preferences for a user.",
+        ephemeral: true,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::AdminRole,
+        name: "adminrole",
+        aliases: &[],
+        args: &[ArgSpec::optional("@role|none", ArgKind::Role, "The role to allow, or `none`. \
+                                                                 Leave it out to see the \
+                                                                 current one.")],
+        options: &[CLEAR_OPTION],
+        permission: Permission::Admin,
+        description: "Show or set the role allowed to run admin commands.",
+        ephemeral: true,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::SetChannel,
+        name: "setchannel",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("kind", ArgKind::Choice(ANNOUNCEMENT_KINDS),
+                              "What to announce there: `all`, or one kind of announcement."),
+            ArgSpec::optional("#channel|none", ArgKind::Channel, "Where to announce it. Defaults \
+                                                                  to this channel; `none` unsets \
+                                                                  it."),
+        ],
+        options: &[CLEAR_OPTION],
+        permission: Permission::Admin,
+        description: "Set where announcements are posted. Each kind can have a channel of its own.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Digest,
+        name: "digest",
+        aliases: &[],
+        args: &[ArgSpec::optional("window|off", ArgKind::Text, "How long to merge a user's \
+                                                                submissions for, e.g. `10m`, or \
+                                                                `off`. Leave it out to see the \
+                                                                current one.")],
+        options: &[],
+        permission: Permission::Admin,
+        description: "Show or set how long bursts of submissions are merged into one announcement.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Recap,
+        name: "recap",
+        aliases: &[],
+        args: &[ArgSpec::optional("period", ArgKind::Choice(&["daily", "weekly"]),
+                                  "Recap the last day, or the last week. Defaults to `daily`.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Recap what tracked users have solved lately.",
+        ephemeral: false,
+        cooldown: 30,
+    },
+    CommandSpec {
+        kind: CommandKind::RecapSchedule,
+        name: "recapschedule",
+        aliases: &["recaps"],
+        args: &[ArgSpec::optional("recaps", ArgKind::Choice(&["daily", "weekly", "both", "off"]),
+                                  "Which recaps to post. Leave everything out to see the current \
+                                   schedule.")],
+        options: &[
+            OptionSpec {
+                name: "hour",
+                kind: ArgKind::Integer { min: 0, max: 23 },
+                description: "The hour of the day (UTC) recaps are posted at.",
+            },
+            OptionSpec {
+                name: "day",
+                kind: ArgKind::Choice(WEEKDAYS),
+                description: "The day of the week weekly recaps are posted on.",
+            },
+        ],
+        permission: Permission::Admin,
+        description: "Show or set when daily and weekly recaps are posted. See `setchannel` for \
+                      where.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Leaderboard,
+        name: "leaderboard",
+        aliases: &["lb"],
+        args: &[ArgSpec::optional("period", ArgKind::Choice(LEADERBOARD_PERIODS),
+                                  "How far back to count solves. Defaults to `week`.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Rank tracked users by points: each problem solved counts once, by difficulty.",
+        ephemeral: false,
+        cooldown: 15,
+    },
+    CommandSpec {
+        kind: CommandKind::PinLeaderboard,
+        name: "pinleaderboard",
+        aliases: &[],
+        args: &[ArgSpec::optional("period", ArgKind::Choice(&["day", "week", "month", "all", "off"]),
+                                  "How far back to count solves, or `off` to stop updating the \
+                                   pinned one. Defaults to `week`.")],
+        options: &[],
+        permission: Permission::Admin,
+        description: "Pin a leaderboard here, kept up to date after every solve.",
+        ephemeral: false,
+        cooldown: 30,
+    },
+    CommandSpec {
+        kind: CommandKind::Points,
+        name: "points",
+        aliases: &[],
+        args: &[ArgSpec::optional("reset", ArgKind::Choice(&["reset"]),
+                                  "Go back to the default points.")],
+        options: &[
+            OptionSpec {
+                name: "easy",
+                kind: ArgKind::Integer { min: 0, max: 100 },
+                description: "Points an easy solve is worth.",
+            },
+            OptionSpec {
+                name: "medium",
+                kind: ArgKind::Integer { min: 0, max: 100 },
+                description: "Points a medium solve is worth.",
+            },
+            OptionSpec {
+                name: "hard",
+                kind: ArgKind::Integer { min: 0, max: 100 },
+                description: "Points a hard solve is worth.",
+            },
+        ],
+        permission: Permission::Admin,
+        description: "Show or set how many points solves of each difficulty are worth.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Season,
+        name: "season",
+        aliases: &[],
+        args: &[ArgSpec::optional("number", ArgKind::Integer { min: 1, max: 1_000_000 },
+                                  "A past season to look back on. Defaults to the current one.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Show the standings of the current season, or of a past one.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Seasons,
+        name: "seasons",
+        aliases: &[],
+        args: &[ArgSpec::optional("length", ArgKind::Choice(&["week", "month", "quarter", "off"]),
+                                  "How long seasons last, or `off` to stop running them. Leave it \
+                                   out to see the current setting.")],
+        options: &[],
+        permission: Permission::Admin,
+        description: "Show or set how long seasons last. Points start over every season.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Compare,
+        name: "compare",
+        aliases: &["vs"],
+        args: &[
+            ArgSpec::required("user1|@member", ArgKind::User,
+                              "A leetcode username, or a member who linked theirs."),
+            ArgSpec::required("user2|@member", ArgKind::User,
+                              "A leetcode username, or a member who linked theirs."),
+        ],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Compare two leetcode users side by side.",
+        ephemeral: false,
+        cooldown: 15,
+    },
+    CommandSpec {
+        kind: CommandKind::Challenge,
+        name: "challenge",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("username|@member", ArgKind::User,
+                              "Who to challenge: a leetcode username tracked here, or a member \
+                               who linked theirs."),
+            ArgSpec::required("problem", ArgKind::Problem, "The problem's title.").variadic(),
+        ],
+        options: &[OptionSpec {
+            name: "deadline",
+            kind: ArgKind::Duration,
+            description: "How long they have to solve it, e.g. `2d` or `12h`. Defaults to a day.",
+        }],
+        permission: Permission::Everyone,
+        description: "Challenge someone to solve a problem before a deadline. Needs a linked \
+                      account.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Challenges,
+        name: "challenges",
+        aliases: &[],
+        args: &[ArgSpec::optional("username|@member", ArgKind::User,
+                                  "A leetcode username, or a member who linked theirs. Defaults \
+                                   to your linked account.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Show someone's challenge record, and their open challenges.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Goal,
+        name: "goal",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Show how this week's group goal is coming along.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::SetGoal,
+        name: "setgoal",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("count|off", ArgKind::Text,
+                              "How many solves everyone's after together each week, or `off`."),
+            ArgSpec::optional("difficulty", ArgKind::Choice(&["any", "easy", "medium", "hard"]),
+                              "Which solves count. Defaults to any."),
+        ],
+        options: &[],
+        permission: Permission::Admin,
+        description: "Set a weekly group goal, e.g. 200 mediums. Milestones are announced as \
+                      they're reached.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::ImportList,
+        name: "importlist",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("name", ArgKind::Text,
+                              "What to call the list, or a built-in one: `blind75` or \
+                               `neetcode150`."),
+            ArgSpec::optional("file", ArgKind::Attachment,
+                              "A JSON array of problem titles, or a CSV with a title on each \
+                               line. Not needed for built-in lists."),
+        ],
+        options: &[],
+        permission: Permission::Admin,
+        description: "Import a problem list for members to work through, replacing any list of \
+                      the same name.",
+        ephemeral: false,
+        cooldown: 30,
+    },
+    CommandSpec {
+        kind: CommandKind::List,
+        name: "list",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("name", ArgKind::Text, "The problem list."),
+            ArgSpec::optional("username|@member", ArgKind::User,
+                              "A leetcode username, or a member who linked theirs. Leave it out \
+                               to see everyone tracked here."),
+        ],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Show how far along a problem list everyone is, or what someone has left.",
+        ephemeral: false,
+        cooldown: 10,
+    },
+    CommandSpec {
+        kind: CommandKind::Follow,
+        name: "follow",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("name", ArgKind::Text, "The problem list."),
+            ArgSpec::optional("username|@member", ArgKind::User,
+                              "A leetcode username, or a member who linked theirs. Defaults to \
+                               your linked account."),
+        ],
+        options: &[],
+        permission: Permission::SelfOnly,
+        description: "Follow a problem list: solves of its problems are announced with progress \
+                      on it.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Unfollow,
+        name: "unfollow",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("name", ArgKind::Text, "The problem list."),
+            ArgSpec::optional("username|@member", ArgKind::User,
+                              "A leetcode username, or a member who linked theirs. Defaults to \
+                               your linked account."),
+        ],
+        options: &[],
+        permission: Permission::SelfOnly,
+        description: "Stop following a problem list.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Reminder,
+        name: "reminder",
+        aliases: &["remindme"],
+        args: &[
+            ArgSpec::optional("hours|off", ArgKind::Text,
+                              "How many hours (1-23) before streaks are checked at midnight UTC \
+                               to remind you, or `off`. Leave it out to see your reminder."),
+            ArgSpec::optional("where", ArgKind::Choice(&["dm", "here"]),
+                              "Whether to DM you, or ping you in this channel. Defaults to DMs."),
+        ],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Get reminded when your verified account's streak is about to be lost.",
+        ephemeral: true,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Freezes,
+        name: "freezes",
+        aliases: &["freeze"],
+        args: &[ArgSpec::optional("username|@member", ArgKind::User,
+                                  "A leetcode username, or a member who linked theirs. Defaults \
+                                   to your linked account.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Show how many streak freezes someone has saved up.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Kudos,
+        name: "kudos",
+        aliases: &["cheers"],
+        args: &[ArgSpec::optional("username|@member", ArgKind::User,
+                                  "A leetcode username, or a member who linked theirs. Leave it \
+                                   out to see who got the most this week.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Show the kudos someone got from reactions to their announcements here.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Help,
+        name: "help",
+        aliases: &["commands"],
+        args: &[ArgSpec::optional("command", ArgKind::Text, "A command to get details on.")],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Get information on supported commands.",
+        ephemeral: true,
+        cooldown: 3,
+    },
+    CommandSpec {
+        kind: CommandKind::Prefix,
+        name: "prefix",
+        aliases: &[],
+        args: &[ArgSpec::optional("prefix", ArgKind::Text, "The new prefix, or `reset` for the \
+                                                            default. Leave it out to see the \
+                                                            current one.")],
+        options: &[],
+        permission: Permission::Admin,
+        description: "Show or set what commands start with here. Mentioning the bot always works.",
+        ephemeral: false,
+        cooldown: 5,
+    },
+    CommandSpec {
+        kind: CommandKind::Clanker,
+        name: "clanker",
+        aliases: &[],
+        args: &[],
+        options: &[],
+        permission: Permission::Everyone,
+        description: "Don't.",
+        ephemeral: false,
+        cooldown: 30,
+    },
+    CommandSpec {
+        kind: CommandKind::Insert,
+        name: "insert",
+        aliases: &[],
+        args: &[
+            ArgSpec::required("username", ArgKind::Text, "The leetcode user to submit as."),
+            ArgSpec::required("outcome", ArgKind::Choice(&["success", "failure"]),
+                              "Whether the submission was accepted."),
+            ArgSpec::required("problem", ArgKind::Problem, "The problem's title.").variadic(),
+        ],
+        options: &[
+            OptionSpec {
+                name: "ago",
+                kind: ArgKind::Duration,
+                description: "Backdate the submission by this long.",
+            },
+            OptionSpec {
+                name: "on",
+                kind: ArgKind::Date,
+                description: "Backdate the submission to (midnight UTC of) this date.",
+            },
+        ],
+        permission: Permission::Owner,
+        description: "Insert a fake submission (debug mode only).",
+        ephemeral: true,
+        cooldown: 0,
+    },
+];
+
+/// Looks up a command by its name or one of its aliases.
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS
+        .iter()
+        .find(|spec| spec.name == name || spec.aliases.contains(&name))
+}
+
+impl CommandSpec {
+    /// e.g. `` `$prefs [username|@member] [announce_fail=true|false] [announce_link=...]` ``
+    pub fn usage(&self, prefix: &str) -> String {
+        let args = self.args.iter().map(ArgSpec::usage);
+        let options = self.options.iter().map(OptionSpec::usage);
+        let signature = std::iter::once(format!("{prefix}{}", self.name))
+            .chain(args)
+            .chain(options)
+            .join(" ");
+
+        format!("`{signature}`")
+    }
+
+    /// A one-line summary, as shown in the command list.
+    pub fn summary(&self, prefix: &str) -> String {
+        format!("{}:  {}", self.usage(prefix), self.description)
+    }
+
+    /// Everything about the command, as shown by `help <command>`.
+    pub fn details(&self, prefix: &str) -> String {
+        let mut output = format!("**{}**\n{}\n", self.usage(prefix), self.description);
+
+        if !self.aliases.is_empty() {
+            output += &format!("Aliases: {}\n",
+                               self.aliases.iter().map(|alias| format!("`{alias}`")).join(", "));
+        }
+
+        for arg in self.args {
+            output += &format!("\t`{}`: {}\n", arg.name, arg.description);
+        }
+        for option in self.options {
+            output += &format!("\t`{}=`: {}\n", option.name, option.description);
+        }
+
+        if self.cooldown > 0 {
+            output += &format!("Cooldown: {}s\n", self.cooldown);
+        }
+
+        output += &format!("Can be used by: {}", self.permission);
+        output
+    }
+
+    /// The command as a slash command: each argument and option becomes an option of its own.
+    fn slash_command(&self) -> CreateCommand {
+        let args = self.args
+            .iter()
+            .map(|arg| arg.kind.slash_option(arg.name, arg.description, arg.required));
+        let options = self.options
+            .iter()
+            .map(|option| option.kind.slash_option(option.name, option.description, false));
+
+        CreateCommand::new(self.name)
+            .description(slash_description(self.description))
+            .set_options(args.chain(options).collect())
+    }
+
+    /// Finds the argument or option called `name` in a slash command (see `slash_name`).
+    pub fn find_slash_option(&self, name: &str) -> Option<(&'static str, ArgKind)> {
+        let args = self.args.iter().map(|arg| (arg.name, arg.kind));
+        let options = self.options.iter().map(|option| (option.name, option.kind));
+
+        args.chain(options).find(|(arg, _)| slash_name(arg) == name)
+    }
+}
+
+/// Gets a help string listing every command.
+pub fn help(prefix: &str) -> String {
+    let mut output = String::from("**Command List:**\n");
+    for spec in COMMANDS {
+        output += &spec.summary(prefix);
+        output += "\n";
+    }
+
+    output += &format!("Use `{prefix}help <command>` for more on a command.");
+    output
+}
+
+/// Every command, as slash commands to register with Discord.
+pub fn slash_commands() -> Vec<CreateCommand> {
+    COMMANDS
+        .iter()
+        .filter(|spec| spec.kind != CommandKind::Insert || is_debug_mode())
+        .map(CommandSpec::slash_command)
+        .collect()
+}
+
+/// Slash command option names have to be lowercase words: `username|@member` becomes `username`,
+/// `@role|none` becomes `role`.
+fn slash_name(name: &str) -> String {
+    name.split('|')
+        .next()
+        .unwrap_or(name)
+        .trim_start_matches(['@', '#'])
+        .replace(' ', "_")
+        .to_lowercase()
+}
+
+/// Slash command descriptions are capped in length, so long ones are cut short.
+fn slash_description(description: &str) -> String {
+    if description.chars().count() <= MAX_SLASH_DESCRIPTION_LENGTH {
+        return String::from(description);
+    }
+
+    let truncated = description.chars().take(MAX_SLASH_DESCRIPTION_LENGTH - 1).collect::<String>();
+    format!("{truncated}…")
+}
@@ -0,0 +1,42 @@
+use crate::lcdb::Store;
+use crate::models::{Comparison, ComparedUser, RecapPeriod, User};
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use std::collections::BTreeSet;
+
+/// Puts `left` and `right` side by side: their stats, what they've been up to this week (up to
+/// `now`, in milliseconds), and which problems they've solved.
+pub fn build(store: &dyn Store, left: User, right: User, now: usize) -> Result<Comparison> {
+    let left_solved = solved(store, &left, now)?;
+    let right_solved = solved(store, &right, now)?;
+
+    Ok(Comparison {
+        both_solved: left_solved.intersection(&right_solved).cloned().collect(),
+        only_left_solved: left_solved.difference(&right_solved).cloned().collect(),
+        only_right_solved: right_solved.difference(&left_solved).cloned().collect(),
+        left: compared(store, left, now)?,
+        right: compared(store, right, now)?,
+    })
+}
+
+/// Titles of every problem `user` has solved, in alphabetical order.
+fn solved(store: &dyn Store, user: &User, now: usize) -> Result<BTreeSet<String>> {
+    Ok(store.query_solved_before(user, now + 1)?.into_iter().collect())
+}
+
+fn compared(store: &dyn Store, mut user: User, now: usize) -> Result<ComparedUser> {
+    // LeetCode doesn't know about our streaks.
+    user.streak = store.query_user(&user.username)?.map_or(0, |stored| stored.streak);
+
+    let since = now.saturating_sub(RecapPeriod::Weekly.length());
+    let week = store.query_submissions_between(&user, since, now + 1)?;
+    let week_solved = week
+        .iter()
+        .filter(|submission| submission.accepted)
+        .unique_by(|submission| &submission.problem.title)
+        .count();
+
+    Ok(ComparedUser { user, week_submissions: week.len(), week_solved })
+}
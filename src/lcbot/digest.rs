@@ -0,0 +1,195 @@
+use crate::models::{self, Submission};
+
+use anyhow::{Result, anyhow};
+use serenity::builder::{CreateEmbed, CreateEmbedAuthor, EditMessage};
+use serenity::model::id::{ChannelId, MessageId};
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// Embed descriptions can't be longer than 4096 characters, nor messages than 2000: a digest this
+// close to either starts over in a new message.
+const MAX_EMBED_DIGEST_LENGTH: usize = 3500;
+const MAX_TEXT_DIGEST_LENGTH: usize = 1800;
+
+/// The digest each user is getting in each channel, by (channel ID, username).
+///
+/// Nothing here is persisted: after a restart, digests start over in a new message.
+static DIGESTS: LazyLock<Mutex<HashMap<(u64, String), Digest>>> = LazyLock::new(Mutex::default);
+
+/// An announcement that more submissions are merged into, as they come in.
+struct Digest {
+    message_id: MessageId,
+    /// Whether the message is an embed, or its plain-text fallback.
+    is_embed: bool,
+    entries: Vec<Entry>,
+    posted_at: Instant,
+    /// How long after it was posted more submissions can be merged into it.
+    window: Duration,
+}
+
+/// A submission, as a line of a digest.
+pub struct Entry {
+    username: String,
+    accepted: bool,
+    line: String,
+}
+
+impl Entry {
+    /// `notes` are mentioned after the submission, e.g. "12/75 on blind75".
+    pub fn new(submission: &Submission, has_link: bool, notes: &[String]) -> Self {
+        let verdict = if submission.accepted { "✅" } else { "❌" };
+        let mut line = format!("{verdict} [{}]({})",
+                               submission.problem.title, submission.problem.link());
+
+        if submission.problem.is_difficulty_known() {
+            line += &format!(" · {}", submission.problem.difficulty);
+        }
+        // Like single announcements, failures always link to the submission.
+        if has_link || !submission.accepted {
+            line += &format!(" · [submission]({})", submission.url);
+        }
+        for note in notes {
+            line += &format!(" · {note}");
+        }
+
+        Self { username: submission.username.clone(), accepted: submission.accepted, line }
+    }
+}
+
+/// Announces a submission in `channel`: in the message already digesting its user's submissions
+/// there, if it was posted less than `window` ago, otherwise on its own (as `embed`, or `text` if
+/// that can't be sent), starting a new digest. Returns the ID of the message it's in.
+pub async fn announce(
+    ctx: &serenity::client::Context,
+    channel: ChannelId,
+    window: Duration,
+    entry: Entry,
+    avatar: Option<&str>,
+    embed: CreateEmbed,
+    text: &str) -> Result<MessageId>
+{
+    let key = (channel.get(), entry.username.clone());
+
+    // Taken out while it's being edited, and put back once it has been.
+    let digest = {
+        let mut digests = DIGESTS.lock().map_err(|_| anyhow!("Digests lock was poisoned."))?;
+        digests.retain(|_, digest| digest.posted_at.elapsed() < digest.window);
+        digests
+            .remove(&key)
+            .filter(|digest| digest.posted_at.elapsed() < window)
+            .filter(|digest| digest.has_room_for(&entry))
+    };
+
+    let digest = match digest {
+        Some(mut digest) => {
+            digest.entries.push(entry);
+            match digest.edit(ctx, channel, avatar).await {
+                Ok(()) => digest,
+                Err(err) => {
+                    // Most likely, the message was deleted.
+                    log::warn!("[digest::announce] Couldn't edit digest in channel {channel}, \
+                                starting a new one: {err}");
+                    let entry = digest.entries.pop().unwrap_or_else(|| unreachable!());
+                    start(ctx, channel, window, entry, embed, text).await?
+                }
+            }
+        }
+        None => start(ctx, channel, window, entry, embed, text).await?,
+    };
+
+    let message_id = digest.message_id;
+    DIGESTS
+        .lock()
+        .map_err(|_| anyhow!("Digests lock was poisoned."))?
+        .insert(key, digest);
+
+    Ok(message_id)
+}
+
+/// Announces a submission on its own, as the start of a new digest.
+async fn start(
+    ctx: &serenity::client::Context,
+    channel: ChannelId,
+    window: Duration,
+    entry: Entry,
+    embed: CreateEmbed,
+    text: &str) -> Result<Digest>
+{
+    let (message, is_embed) = super::send_embed(ctx, channel, embed, text).await?;
+
+    Ok(Digest {
+        message_id: message.id,
+        is_embed,
+        entries: vec![entry],
+        posted_at: Instant::now(),
+        window,
+    })
+}
+
+impl Digest {
+    fn username(&self) -> &str {
+        self.entries.first().map(|entry| entry.username.as_str()).unwrap_or("")
+    }
+
+    /// Whether `entry` can be merged in without the message getting too long.
+    fn has_room_for(&self, entry: &Entry) -> bool {
+        let max_length = match self.is_embed {
+            true => MAX_EMBED_DIGEST_LENGTH,
+            false => MAX_TEXT_DIGEST_LENGTH,
+        };
+        let length = self.entries
+            .iter()
+            .chain(std::iter::once(entry))
+            .map(|entry| entry.line.chars().count() + 2)
+            .sum::<usize>();
+
+        length < max_length
+    }
+
+    /// e.g. "alice solved 3 problems", or "alice made 4 submissions, 3 accepted"
+    fn headline(&self) -> String {
+        let username = self.username();
+        let total = self.entries.len();
+        let accepted = self.entries.iter().filter(|entry| entry.accepted).count();
+
+        match accepted {
+            _ if accepted == total => format!("{username} solved {total} problems"),
+            0 => format!("{username} made {total} attempts"),
+            _ => format!("{username} made {total} submissions, {accepted} accepted"),
+        }
+    }
+
+    /// Replaces the digest's message with every submission it has so far.
+    async fn edit(
+        &self,
+        ctx: &serenity::client::Context,
+        channel: ChannelId,
+        avatar: Option<&str>) -> Result<()>
+    {
+        let headline = self.headline();
+        let lines = self.entries.iter().map(|entry| entry.line.as_str());
+
+        let message = if self.is_embed {
+            let mut author = CreateEmbedAuthor::new(headline)
+                .url(format!("https://leetcode.com/u/{}/", self.username()));
+            if let Some(avatar) = avatar {
+                author = author.icon_url(avatar);
+            }
+
+            let embed = CreateEmbed::new()
+                .author(author)
+                .description(lines.collect::<Vec<_>>().join("\n"))
+                .color(models::LEETCODE_COLOR);
+            EditMessage::new().embed(embed)
+        } else {
+            let text = lines.fold(format!("{headline}:"), |text, line| text + "\n\t" + line);
+            EditMessage::new().content(text)
+        };
+
+        channel.edit_message(&ctx.http, self.message_id, message).await?;
+
+        Ok(())
+    }
+}
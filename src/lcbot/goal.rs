@@ -0,0 +1,91 @@
+use crate::lcdb::Store;
+use crate::models::{self, AnnouncementKind, GroupGoal, Render};
+
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use itertools::Itertools;
+use serenity::builder::CreateEmbedAuthor;
+
+/// Where `guild_id`'s weekly group goal stands at `now`, if it has one.
+///
+/// Every accepted solve of the goal's difficulty this week counts, each problem once per user.
+pub fn build(store: &dyn Store, guild_id: u64, now: DateTime<Utc>) -> Result<Option<GroupGoal>> {
+    let settings = store.query_guild_settings(guild_id)?;
+    let Some(target) = settings.goal_count else {
+        return Ok(None);
+    };
+
+    let week_start = models::week_start(now);
+    let since = week_start.timestamp_millis().max(0) as usize;
+    let until = (week_start + TimeDelta::weeks(1)).timestamp_millis().max(0) as usize;
+    let now = now.timestamp_millis().max(0) as usize;
+
+    let mut contributors = Vec::new();
+    for user in store.query_guild_tracked_users(guild_id)? {
+        let solves = store
+            .query_submissions_between(&user, since, now + 1)?
+            .into_iter()
+            .filter(|submission| submission.accepted)
+            .filter(|submission| settings.goal_difficulty.as_ref().is_none_or(|difficulty| {
+                submission.problem.difficulty.eq_ignore_ascii_case(difficulty)
+            }))
+            .unique_by(|submission| submission.problem.title.clone())
+            .count() as u64;
+
+        if solves > 0 {
+            contributors.push((user.username, solves));
+        }
+    }
+    contributors.sort_by(|(a, a_solves), (b, b_solves)| b_solves.cmp(a_solves).then(a.cmp(b)));
+
+    Ok(Some(GroupGoal {
+        target,
+        difficulty: settings.goal_difficulty,
+        progress: contributors.iter().map(|(_, solves)| solves).sum(),
+        contributors,
+        since,
+        until,
+    }))
+}
+
+/// Announces the milestone `guild_id`'s weekly goal has reached at `now`, if it's one that
+/// hasn't been announced yet this week.
+pub async fn announce_milestone(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    guild_id: u64,
+    now: DateTime<Utc>) -> Result<()>
+{
+    let Some(goal) = build(store, guild_id, now)? else {
+        return Ok(());
+    };
+    let Some(milestone) = goal.milestone() else {
+        return Ok(());
+    };
+
+    // Milestones announced in earlier weeks don't count anymore.
+    let mut settings = store.query_guild_settings(guild_id)?;
+    let announced = match settings.goal_milestone_at {
+        Some(at) if at >= goal.since => settings.goal_milestone,
+        _ => 0,
+    };
+    if milestone <= announced {
+        return Ok(());
+    }
+
+    settings.goal_milestone = milestone;
+    settings.goal_milestone_at = Some(now.timestamp_millis().max(0) as usize);
+    store.update_guild_settings(guild_id, &settings)?;
+    log::info!("Guild {guild_id} reached {milestone}% of its weekly goal.");
+
+    let headline = match milestone {
+        100 => String::from("🎉 Weekly goal reached!"),
+        _ => format!("🎯 {milestone}% of the weekly goal reached!"),
+    };
+    let embed = goal.render_embed().author(CreateEmbedAuthor::new(&headline));
+    let text = format!("{headline}\n{}", goal.render_text());
+
+    super::announce_embed(ctx, store, guild_id, AnnouncementKind::Goals, embed, &text).await?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::lcdb::Store;
+use crate::models::Kudos;
+
+use anyhow::Result;
+use serenity::model::channel::Reaction;
+
+/// Counts `reaction` (added at `now`, in milliseconds) as kudos, if it's to a submission
+/// announcement. Bots, and users cheering for themselves, don't count.
+pub fn give(store: &dyn Store, reaction: &Reaction, now: usize) -> Result<()> {
+    let Some(giver_id) = reaction.user_id else {
+        return Ok(());
+    };
+    if reaction.member.as_ref().is_some_and(|member| member.user.bot) {
+        return Ok(());
+    }
+
+    let Some(announcement) = store.query_submission_announcement(reaction.message_id.get())?
+    else {
+        return Ok(());
+    };
+    let giver = store.query_linked_username(giver_id.get())?;
+    if giver.is_some_and(|giver| giver.eq_ignore_ascii_case(&announcement.username)) {
+        return Ok(());
+    }
+
+    store.insert_kudos(&Kudos {
+        message_id: announcement.message_id,
+        giver_id: giver_id.get(),
+        emoji: reaction.emoji.to_string(),
+        given_at: now,
+    })?;
+    log::debug!("[kudos::give] {giver_id} reacted {} to {}'s announcement.", reaction.emoji,
+                announcement.username);
+
+    Ok(())
+}
+
+/// Stops counting `reaction` as kudos, now that it was removed.
+pub fn take_back(store: &dyn Store, reaction: &Reaction) -> Result<()> {
+    let Some(giver_id) = reaction.user_id else {
+        return Ok(());
+    };
+
+    store.delete_kudos(reaction.message_id.get(), giver_id.get(), &reaction.emoji.to_string())
+}
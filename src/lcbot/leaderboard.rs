@@ -0,0 +1,140 @@
+use crate::lcdb::Store;
+use crate::models::{Leaderboard, LeaderboardEntry, LeaderboardPeriod, Render};
+
+use anyhow::Result;
+use itertools::Itertools;
+use serenity::builder::EditMessage;
+use serenity::http::{HttpError, StatusCode};
+use serenity::model::id::{ChannelId, MessageId};
+
+use std::cmp::Reverse;
+
+/// Ranks `guild_id`'s tracked users by the points their accepted solves over `period` (up to
+/// `now`, in milliseconds) are worth.
+pub fn build(store: &dyn Store, guild_id: u64, period: LeaderboardPeriod, now: usize)
+    -> Result<Leaderboard>
+{
+    let settings = store.query_guild_settings(guild_id)?;
+    let since = period.length().map_or(0, |length| now.saturating_sub(length));
+    let entries = rank(store, guild_id, since, now + 1)?;
+
+    Ok(Leaderboard { period, weights: settings.point_weights(), entries, updated_at: now })
+}
+
+/// Ranks `guild_id`'s tracked users by the points their accepted solves from `since` up to (not
+/// including) `until` are worth, best first. Each problem only counts once per user.
+pub fn rank(store: &dyn Store, guild_id: u64, since: usize, until: usize)
+    -> Result<Vec<LeaderboardEntry>>
+{
+    let settings = store.query_guild_settings(guild_id)?;
+
+    let mut entries = Vec::new();
+    for user in store.query_guild_tracked_users(guild_id)? {
+        let mut submissions = store.query_submissions_between(&user, since, until)?;
+        submissions.retain(|submission| submission.accepted);
+
+        // Oldest first, so that each problem is counted at its first solve.
+        submissions.sort_by_key(|submission| submission.timestamp);
+        let solves = submissions
+            .iter()
+            .unique_by(|submission| &submission.problem.title)
+            .collect::<Vec<_>>();
+
+        let Some(last) = solves.last() else {
+            continue;
+        };
+
+        entries.push(LeaderboardEntry {
+            username: user.username.clone(),
+            points: solves.iter().map(|submission| settings.points(&submission.problem)).sum(),
+            solved: solves.len(),
+            reached_at: last.timestamp,
+        });
+    }
+
+    entries.sort_by_key(|entry| (Reverse(entry.points), entry.reached_at));
+
+    Ok(entries)
+}
+
+/// Brings `guild_id`'s pinned leaderboard (if it has one) up to date.
+/// If the message is gone, the guild stops having one.
+pub async fn refresh_pinned(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    guild_id: u64,
+    now: usize) -> Result<()>
+{
+    let settings = store.query_guild_settings(guild_id)?;
+    let (Some(channel_id), Some(message_id)) =
+        (settings.leaderboard_channel, settings.leaderboard_message)
+    else {
+        return Ok(());
+    };
+
+    let period = settings
+        .leaderboard_period
+        .as_deref()
+        .and_then(LeaderboardPeriod::from_name)
+        .unwrap_or(LeaderboardPeriod::All);
+    let leaderboard = build(store, guild_id, period, now)?;
+
+    let edit = EditMessage::new().content("").embed(leaderboard.render_embed());
+    let result = ChannelId::new(channel_id)
+        .edit_message(&ctx.http, MessageId::new(message_id), edit)
+        .await;
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(response)))
+            if response.status_code == StatusCode::NOT_FOUND =>
+        {
+            log::warn!("[refresh_pinned] Pinned leaderboard of guild {guild_id} was deleted, \
+                        forgetting it.");
+            unpin(store, guild_id)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Posts a leaderboard of `period` in `channel`, pins it, and keeps it up to date from then on
+/// (instead of whichever one the guild had before).
+///
+/// Returns whether it could be pinned: it's kept up to date either way.
+pub async fn pin(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    guild_id: u64,
+    channel: ChannelId,
+    period: LeaderboardPeriod,
+    now: usize) -> Result<bool>
+{
+    let leaderboard = build(store, guild_id, period, now)?;
+    let (message, _) =
+        super::send_embed(ctx, channel, leaderboard.render_embed(), &leaderboard.render_text())
+            .await?;
+
+    let pinned = message
+        .pin(&ctx.http)
+        .await
+        .inspect_err(|err| log::warn!("[pin] Couldn't pin leaderboard in channel {channel}: {err}"))
+        .is_ok();
+
+    let mut settings = store.query_guild_settings(guild_id)?;
+    settings.leaderboard_channel = Some(channel.get());
+    settings.leaderboard_message = Some(message.id.get());
+    settings.leaderboard_period = Some(String::from(period.name()));
+    store.update_guild_settings(guild_id, &settings)?;
+
+    Ok(pinned)
+}
+
+/// Stops keeping `guild_id`'s pinned leaderboard up to date. The message itself is left alone.
+pub fn unpin(store: &dyn Store, guild_id: u64) -> Result<()> {
+    let mut settings = store.query_guild_settings(guild_id)?;
+    settings.leaderboard_channel = None;
+    settings.leaderboard_message = None;
+    settings.leaderboard_period = None;
+    store.update_guild_settings(guild_id, &settings)
+}
+
@@ -0,0 +1,221 @@
+use crate::lcdb::Store;
+use crate::models::{self, ListProgress, ProblemList, Submission, User};
+
+use anyhow::{Context, Result, bail};
+use itertools::Itertools;
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// Lists that can be imported by name, without a file.
+const BUILTIN_LISTS: [(&str, &str); 2] = [
+    ("blind75", include_str!("../../lists/blind75.json")),
+    ("neetcode150", include_str!("../../lists/neetcode150.json")),
+];
+
+// Lists hold at most 1000 problems, and are imported from files of at most 256 KiB.
+const MAX_LIST_PROBLEMS: usize = 1000;
+pub const MAX_LIST_FILE_BYTES: usize = 256 * 1024;
+
+/// The names of the lists that can be imported without a file.
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_LISTS.iter().map(|(name, _)| *name)
+}
+
+/// The problem titles of the built-in list called `name`, if there is one.
+pub fn builtin(name: &str) -> Option<Result<Vec<String>>> {
+    BUILTIN_LISTS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, contents)| parse(contents))
+}
+
+/// Parses problem titles out of a file: either a JSON array of titles (or of objects with a
+/// `title`), or a CSV with a title at the start of each line.
+pub fn parse(contents: &str) -> Result<Vec<String>> {
+    let titles = match contents.trim_start().starts_with('[') {
+        true => serde_json::from_str::<Vec<serde_json::Value>>(contents)
+            .context("Couldn't read the file as a JSON array.")?
+            .into_iter()
+            .map(|entry| match entry {
+                serde_json::Value::String(title) => Some(title),
+                entry => entry.get("title")?.as_str().map(String::from),
+            })
+            .collect::<Option<Vec<_>>>()
+            .context("Every entry of a JSON list should be a title, or have a `title`.")?,
+        false => contents
+            .lines()
+            .map(first_csv_field)
+            .filter(|title| !title.is_empty())
+            // Skip the header, if there is one.
+            .skip_while(|title| title.eq_ignore_ascii_case("title"))
+            .collect(),
+    };
+
+    let titles = titles
+        .into_iter()
+        .map(|title| title.trim().to_owned())
+        .filter(|title| !title.is_empty())
+        .unique_by(|title| title.to_lowercase())
+        .collect::<Vec<_>>();
+
+    if titles.is_empty() {
+        bail!("Couldn't find any problems in the file.");
+    }
+    if titles.len() > MAX_LIST_PROBLEMS {
+        bail!("Lists can hold at most {MAX_LIST_PROBLEMS} problems.");
+    }
+
+    Ok(titles)
+}
+
+/// The first field of a CSV line, which may be "quoted" (with "" for a quote inside).
+fn first_csv_field(line: &str) -> String {
+    let line = line.trim();
+    let Some(quoted) = line.strip_prefix('"') else {
+        return line.split(',').next().unwrap_or("").to_owned();
+    };
+
+    let mut field = String::new();
+    let mut chars = quoted.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => break,
+            c => field.push(c),
+        }
+    }
+
+    field
+}
+
+/// A problem's slug, as in its link, from its title: e.g. `Pow(x, n)` becomes `powx-n`.
+fn slugify(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .map(|word| word.chars().filter(char::is_ascii_alphanumeric).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .join("-")
+}
+
+/// Stores a problem list called `name` in `guild_id`, replacing any list of the same name.
+///
+/// Titles are matched (ignoring case) to the problems we know of, so that they match the
+/// submissions that solve them; the others are added to Problems.
+pub fn import(store: &dyn Store, guild_id: u64, name: &str, titles: Vec<String>)
+    -> Result<ProblemList>
+{
+    let mut problems = Vec::new();
+    for title in titles {
+        let known = store
+            .query_problem_titles(&title, MAX_LIST_PROBLEMS)?
+            .into_iter()
+            .find(|known| known.eq_ignore_ascii_case(&title));
+
+        let title = match known {
+            Some(known) => known,
+            None => {
+                store.insert_problem(&models::Problem {
+                    url: slugify(&title),
+                    title: title.clone(),
+                    difficulty: String::from(models::UNKNOWN_DIFFICULTY),
+                })?;
+                title
+            }
+        };
+        problems.push(title);
+    }
+
+    let list = ProblemList { name: name.to_owned(), problems };
+    store.replace_problem_list(guild_id, &list)?;
+    log::info!("Imported list {name} of {} problems into guild {guild_id}.", list.problems.len());
+
+    Ok(list)
+}
+
+/// The titles (in lowercase) of every problem `user` has solved, up to `now` (in milliseconds).
+fn solved_titles(store: &dyn Store, user: &User, now: usize) -> Result<HashSet<String>> {
+    Ok(store
+        .query_solved_before(user, now + 1)?
+        .into_iter()
+        .map(|title| title.to_lowercase())
+        .collect())
+}
+
+/// How far along `list` every one of `guild_id`'s tracked users is at `now` (in milliseconds).
+/// Only users who solved any of it show up.
+pub fn progress(store: &dyn Store, guild_id: u64, list: &ProblemList, now: usize)
+    -> Result<ListProgress>
+{
+    let mut members = Vec::new();
+    for user in store.query_guild_tracked_users(guild_id)? {
+        let solved = solved_titles(store, &user, now)?;
+        let count = list.problems.iter().filter(|p| solved.contains(&p.to_lowercase())).count();
+        if count > 0 {
+            members.push((user.username, count));
+        }
+    }
+    members.sort_by_key(|(username, solved)| (Reverse(*solved), username.clone()));
+
+    Ok(ListProgress {
+        list: list.name.clone(),
+        total: list.problems.len(),
+        members,
+        remaining: None,
+    })
+}
+
+/// How far along `list` `user` is at `now` (in milliseconds), and what they have left.
+pub fn user_progress(store: &dyn Store, list: &ProblemList, user: &User, now: usize)
+    -> Result<ListProgress>
+{
+    let solved = solved_titles(store, user, now)?;
+    let (done, remaining): (Vec<_>, Vec<_>) = list
+        .problems
+        .iter()
+        .cloned()
+        .partition(|problem| solved.contains(&problem.to_lowercase()));
+
+    Ok(ListProgress {
+        list: list.name.clone(),
+        total: list.problems.len(),
+        members: vec![(user.username.clone(), done.len())],
+        remaining: Some(remaining),
+    })
+}
+
+/// e.g. "12/75 on blind75", for every list its user follows in `guild_id` that `submission`'s
+/// problem is on.
+pub fn progress_notes(store: &dyn Store, guild_id: u64, submission: &Submission)
+    -> Result<Vec<String>>
+{
+    let followed = store.query_followed_lists(guild_id, &submission.username)?;
+    if followed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(user) = store.query_user(&submission.username)? else {
+        return Ok(Vec::new());
+    };
+    let solved = solved_titles(store, &user, submission.timestamp)?;
+
+    let mut notes = Vec::new();
+    for name in followed {
+        let Some(list) = store.query_problem_list(guild_id, &name)? else {
+            continue;
+        };
+        let title = &submission.problem.title;
+        if !list.problems.iter().any(|problem| problem.eq_ignore_ascii_case(title)) {
+            continue;
+        }
+
+        let count = list.problems.iter().filter(|p| solved.contains(&p.to_lowercase())).count();
+        notes.push(format!("{count}/{} on {name}", list.problems.len()));
+    }
+
+    Ok(notes)
+}
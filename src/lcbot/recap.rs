@@ -0,0 +1,119 @@
+use crate::lcdb::Store;
+use crate::models::{self, AnnouncementKind, Recap, RecapPeriod, RecapUser, Render};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+/// Builds a recap of what `guild_id`'s tracked users got up to over the `period` leading up to
+/// `until` (in milliseconds), from the Submissions table.
+pub fn build(store: &dyn Store, guild_id: u64, period: RecapPeriod, until: usize)
+    -> Result<Recap>
+{
+    let since = until.saturating_sub(period.length());
+
+    let mut users = Vec::new();
+    let mut attempts: HashMap<String, (models::Problem, usize)> = HashMap::new();
+    let mut longest_streak: Option<(String, u64)> = None;
+
+    for user in store.query_guild_tracked_users(guild_id)? {
+        let streak = store.query_streak(&user)?;
+        if streak > 0 && longest_streak.as_ref().is_none_or(|(_, longest)| streak > *longest) {
+            longest_streak = Some((user.username.clone(), streak));
+        }
+
+        let submissions = store.query_submissions_between(&user, since, until)?;
+        if submissions.is_empty() {
+            continue;
+        }
+
+        for submission in &submissions {
+            attempts
+                .entry(submission.problem.title.clone())
+                .or_insert_with(|| (submission.problem.clone(), 0))
+                .1 += 1;
+        }
+
+        let solved_before = store
+            .query_solved_before(&user, since)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut recap_user = RecapUser {
+            username: user.username.clone(),
+            submissions: submissions.len(),
+            ..Default::default()
+        };
+        let solved = submissions
+            .iter()
+            .filter(|submission| submission.accepted)
+            .unique_by(|submission| &submission.problem.title);
+        for submission in solved {
+            match submission.problem.difficulty.as_str() {
+                "Easy" => recap_user.easy_solved += 1,
+                "Medium" => recap_user.medium_solved += 1,
+                "Hard" => recap_user.hard_solved += 1,
+                _ => recap_user.other_solved += 1,
+            }
+            if !solved_before.contains(&submission.problem.title) {
+                recap_user.new_solved += 1;
+            }
+        }
+
+        users.push(recap_user);
+    }
+
+    users.sort_by_key(|user| (Reverse(user.total_solved()), Reverse(user.submissions)));
+
+    // Ties go to whichever problem comes first alphabetically, so that recaps are reproducible.
+    let most_attempted = attempts
+        .into_values()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.title.cmp(&a.title)));
+
+    let most_cheered = store.query_kudos_totals(guild_id, since, until)?.into_iter().next();
+
+    Ok(Recap { period, since, until, users, most_attempted, longest_streak, most_cheered })
+}
+
+/// Posts every recap that's due at `now` (expected to be on the hour), in every guild.
+pub async fn post_due(ctx: &serenity::client::Context, store: &dyn Store, now: DateTime<Utc>)
+    -> Result<()>
+{
+    let until = now.timestamp_millis().max(0) as usize;
+
+    // One guild's trouble mustn't keep the others from getting their recaps.
+    for guild_id in store.query_guilds()? {
+        let settings = match store.query_guild_settings(guild_id) {
+            Ok(settings) => settings,
+            Err(err) => {
+                log::error!("Couldn't get the settings of guild {guild_id} for recaps: {err}");
+                continue;
+            }
+        };
+
+        for period in settings.due_recaps(now) {
+            log::info!("Posting {} recap to guild {guild_id}.", period.name());
+
+            let recap = match build(store, guild_id, period, until) {
+                Ok(recap) => recap,
+                Err(err) => {
+                    log::error!("Couldn't build {} recap of guild {guild_id}: {err}",
+                                period.name());
+                    continue;
+                }
+            };
+            let result = super::announce_embed(
+                ctx, store, guild_id, AnnouncementKind::Recaps, recap.render_embed(),
+                &recap.render_text()).await;
+
+            if let Err(err) = result {
+                log::error!("Couldn't post {} recap to guild {guild_id}: {err}", period.name());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::lcdb::Store;
+use crate::models::StreakReminder;
+
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use serenity::model::id::{ChannelId, UserId};
+
+/// Reminds every member whose reminder is due at `now` (expected to be on the hour) and whose
+/// streak is at risk: they have one, but haven't solved anything since the last check.
+pub async fn send_due(ctx: &serenity::client::Context, store: &dyn Store, now: DateTime<Utc>)
+    -> Result<()>
+{
+    for reminder in store.query_streak_reminders()? {
+        if !reminder.is_due(now) {
+            continue;
+        }
+
+        if let Err(err) = remind(ctx, store, &reminder, now).await {
+            log::error!("Couldn't send streak reminder to {}: {err}", reminder.discord_id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn remind(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    reminder: &StreakReminder,
+    now: DateTime<Utc>) -> Result<()>
+{
+    let Some(username) = store.query_linked_username(reminder.discord_id)? else {
+        return Ok(());
+    };
+    // The link may have changed since the reminder was set.
+    if !store.is_verified_owner(reminder.discord_id, &username)? {
+        return Ok(());
+    }
+    let Some(user) = store.query_user(&username)? else {
+        return Ok(());
+    };
+
+    let streak = store.query_streak(&user)?;
+    if streak == 0 {
+        return Ok(());
+    }
+
+    // Same check streaks get at midnight.
+    if store.is_active(&user)? {
+        return Ok(());
+    }
+
+    let midnight = now.date_naive().and_time(Default::default()).and_utc();
+    let cutoff = (midnight + TimeDelta::days(1)).timestamp();
+    let msg = match store.query_streak_freezes(&user)? {
+        0 => format!("⏰ **{username}**'s {streak}-day streak ends <t:{cutoff}:R> unless they \
+                      solve something before then!"),
+        freezes => format!("⏰ **{username}** will spend one of their {freezes} streak freezes \
+                            <t:{cutoff}:R> unless they solve something before then!"),
+    };
+
+    log::info!("Reminding {} that {username}'s streak is at risk.", reminder.discord_id);
+    match reminder.channel_id {
+        Some(channel_id) => {
+            ChannelId::new(channel_id)
+                .say(&ctx.http, format!("<@{}> {msg}", reminder.discord_id))
+                .await?;
+        }
+        None => {
+            UserId::new(reminder.discord_id)
+                .create_dm_channel(&ctx.http)
+                .await?
+                .say(&ctx.http, msg)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
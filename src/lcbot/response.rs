@@ -0,0 +1,163 @@
+use anyhow::{Result, anyhow};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::model::application::{ButtonStyle, ComponentInteraction};
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// Discord refuses messages longer than 2000 characters.
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+// Custom ID prefix of the buttons under a paginated response.
+const PAGE_BUTTON: &str = "page";
+
+// Pages can be flipped through for 15 minutes, or 900 seconds
+const PAGES_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// Every paginated response still being flipped through, by ID.
+static PAGINATED: LazyLock<Mutex<HashMap<u64, Paginated>>> = LazyLock::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+struct Paginated {
+    pages: Vec<String>,
+    sent_at: Instant,
+}
+
+/// A reply, split into pages that each fit in a message.
+///
+/// Short replies are a single page and are sent as-is; longer ones are sent one page at a time,
+/// with buttons to flip between them.
+pub struct Response {
+    pages: Vec<String>,
+}
+
+impl Response {
+    /// Splits `content` into pages, between lines where possible.
+    pub fn new(content: &str) -> Self {
+        let mut pages = Vec::new();
+        let mut page = String::new();
+
+        for line in content.lines().flat_map(split_long_line) {
+            // +1 for the newline joining it to the page
+            let length = page.chars().count() + line.chars().count() + 1;
+            if !page.is_empty() && length > MAX_MESSAGE_LENGTH {
+                pages.push(std::mem::take(&mut page));
+            }
+
+            if !page.is_empty() {
+                page.push('\n');
+            }
+            page.push_str(&line);
+        }
+
+        if !page.is_empty() {
+            pages.push(page);
+        }
+
+        Self { pages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// The first page, with buttons to flip to the others if there are any.
+    pub fn first_page(self) -> (String, Vec<CreateActionRow>) {
+        let first = self.pages.first().cloned().unwrap_or_default();
+        if self.pages.len() <= 1 {
+            return (first, Vec::new());
+        }
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let buttons = page_buttons(id, 0, self.pages.len());
+
+        match PAGINATED.lock() {
+            Ok(mut paginated) => {
+                paginated.retain(|_, p| p.sent_at.elapsed() < PAGES_TIMEOUT);
+                paginated.insert(id, Paginated { pages: self.pages, sent_at: Instant::now() });
+            }
+            Err(_) => log::error!("[first_page] Paginated responses lock was poisoned."),
+        }
+
+        (first, vec![buttons])
+    }
+}
+
+/// Returns whether a component is one of the buttons under a paginated response.
+pub fn is_page_button(custom_id: &str) -> bool {
+    custom_id.split(':').next() == Some(PAGE_BUTTON)
+}
+
+/// Flips a paginated response to the page its button points at.
+pub async fn handle_page_button(
+    ctx: &serenity::client::Context,
+    component: &ComponentInteraction) -> Result<()>
+{
+    let custom_id = &component.data.custom_id;
+    let mut fields = custom_id.split(':').skip(1);
+    let (Some(Ok(id)), Some(Ok(index))) =
+        (fields.next().map(str::parse::<u64>), fields.next().map(str::parse::<usize>))
+    else {
+        return Err(anyhow!("Malformed page button: {custom_id}"));
+    };
+
+    let page = {
+        let paginated = PAGINATED
+            .lock()
+            .map_err(|_| anyhow!("Paginated responses lock was poisoned."))?;
+
+        paginated
+            .get(&id)
+            .filter(|p| p.sent_at.elapsed() < PAGES_TIMEOUT)
+            .and_then(|p| Some((p.pages.get(index)?.clone(), p.pages.len())))
+    };
+
+    let response = match page {
+        Some((content, count)) => CreateInteractionResponseMessage::new()
+            .content(content)
+            .components(vec![page_buttons(id, index, count)]),
+        // The pages are gone (expired, or the bot restarted): stop offering to flip them.
+        None => CreateInteractionResponseMessage::new()
+            .components(vec![]),
+    };
+    component
+        .create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(response))
+        .await?;
+
+    Ok(())
+}
+
+/// Previous/next buttons for page `page` (of `count`) of paginated response `id`.
+fn page_buttons(id: u64, page: usize, count: usize) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{PAGE_BUTTON}:{id}:{}", page.saturating_sub(1)))
+            .label("◀ Previous")
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(format!("{PAGE_BUTTON}:{id}:current"))
+            .label(format!("{}/{count}", page + 1))
+            .style(ButtonStyle::Secondary)
+            .disabled(true),
+        CreateButton::new(format!("{PAGE_BUTTON}:{id}:{}", page + 1))
+            .label("Next ▶")
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= count),
+    ])
+}
+
+/// Splits a line too long for a single message into pieces that fit.
+fn split_long_line(line: &str) -> Vec<String> {
+    let chars = line.chars().collect::<Vec<_>>();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+
+    chars
+        .chunks(MAX_MESSAGE_LENGTH)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
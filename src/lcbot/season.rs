@@ -0,0 +1,85 @@
+use super::leaderboard;
+use crate::lcdb::Store;
+use crate::models::{AnnouncementKind, Render, Season};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+// The midnight scheduler can wake up a little early; seasons ending within a minute of it are
+// rolled over anyway.
+const ROLLOVER_SLACK_MILLIS: usize = 60 * 1000;
+
+/// The current season of `guild_id`, with its standings as of `now` (in milliseconds).
+/// None if the guild doesn't run seasons.
+pub fn current(store: &dyn Store, guild_id: u64, now: usize) -> Result<Option<Season>> {
+    let settings = store.query_guild_settings(guild_id)?;
+    let Some((length, started_at)) = settings.current_season() else {
+        return Ok(None);
+    };
+
+    let ended_at = length.end(started_at);
+    let standings = leaderboard::rank(store, guild_id, started_at, (now + 1).min(ended_at))?;
+
+    Ok(Some(Season { number: settings.season_number, started_at, ended_at, standings,
+                     is_over: false }))
+}
+
+/// Ends every season that's over by `now`: archives its final standings, announces its winners,
+/// and starts the next one.
+///
+/// Intended to be run by the midnight scheduler.
+pub async fn roll_over(ctx: &serenity::client::Context, store: &dyn Store, now: DateTime<Utc>)
+    -> Result<()>
+{
+    let now = now.timestamp_millis().max(0) as usize;
+
+    for guild_id in store.query_guilds()? {
+        if let Err(err) = roll_over_guild(ctx, store, guild_id, now).await {
+            log::error!("Couldn't roll over season of guild {guild_id}: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn roll_over_guild(
+    ctx: &serenity::client::Context,
+    store: &dyn Store,
+    guild_id: u64,
+    now: usize) -> Result<()>
+{
+    let mut settings = store.query_guild_settings(guild_id)?;
+
+    // If the bot was down for a while, several seasons may have ended since.
+    while let Some((length, started_at)) = settings.current_season() {
+        let ended_at = length.end(started_at);
+        if ended_at > now + ROLLOVER_SLACK_MILLIS {
+            break;
+        }
+
+        let season = Season {
+            number: settings.season_number,
+            started_at,
+            ended_at,
+            standings: leaderboard::rank(store, guild_id, started_at, ended_at)?,
+            is_over: true,
+        };
+        store.archive_season(guild_id, &season)?;
+
+        settings.season_number += 1;
+        settings.season_start = Some(ended_at);
+        store.update_guild_settings(guild_id, &settings)?;
+        log::info!("Guild {guild_id} is now in season {}.", settings.season_number);
+
+        let next = format!("Season {} starts now, and ends <t:{}:R>.",
+                           settings.season_number, length.end(ended_at) / 1000);
+        let embed = season.render_embed().field("Up next", &next, false);
+        let text = format!("{}{next}", season.render_text());
+
+        super::announce_embed(ctx, store, guild_id, AnnouncementKind::Seasons, embed, &text)
+            .await?;
+    }
+
+    Ok(())
+}
+
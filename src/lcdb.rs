@@ -5,104 +5,618 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::models::{self, AnnouncementPreferences};
 
+mod memory;
+pub use memory::MemoryStore;
+
 type DBResult<T> = Result<T, rusqlite::Error>;
 
-fn connect() -> DBResult<Connection> {
-    Connection::open("db/leek.db")
+/// Everything the bot needs to persist: users, their preferences, submissions, problems, the
+/// recent (announcement) cache and per-guild settings.
+///
+/// Users, submissions and problems are global; tracking, preferences and settings are scoped to a
+/// guild (a Discord server), identified by its raw ID.
+///
+/// `SqliteStore` is what the bot runs on; `MemoryStore` keeps everything in memory, which is handy
+/// for exercising the bot's logic without a database file.
+pub trait Store: Send + Sync {
+    /////*============== SUBMISSION QUERIES ==============*/
+    /// Gathers all recent submissions for a user.
+    fn query_submissions_recent_all(&self, user: &models::User) -> Result<Vec<models::Submission>>;
+
+    /// Gathers every submission `user` made from `since` up to (not including) `until`, newest
+    /// first. Both are timestamps in milliseconds.
+    fn query_submissions_between(&self, user: &models::User, since: usize, until: usize)
+        -> Result<Vec<models::Submission>>;
+
+    /// Returns the title of every problem `user` solved before `before` (in milliseconds).
+    fn query_solved_before(&self, user: &models::User, before: usize) -> Result<Vec<String>>;
+
+    /// Inserts a Submission into the database.
+    /// Returns `true` if it was newly added, false otherwise.
+    fn insert_submission(&self, submission: &models::Submission) -> Result<bool>;
+
+    /// Inserts a fake (debug) submission for `user`, submitted at `timestamp` (in milliseconds).
+    fn insert_fake_submission(
+        &self,
+        user: &models::User,
+        problem_name: String,
+        accepted: bool,
+        timestamp: usize,
+    ) -> Result<()>;
+
+    /////*============== RECENT CACHE QUERIES ==============*/
+    /// Queries the database for submissions that haven't already been announced to the server.
+    fn query_uncached_submissions(&self, user: &models::User) -> Result<Vec<models::Submission>>;
+
+    /// Adds the (problem, user) entry into the recent cache if it doesn't exist.
+    /// Returns `true` if it was newly added, false otherwise.
+    fn insert_cache_submission(&self, submission: &models::Submission) -> Result<bool>;
+
+    /// Removes everything older than `models::RECENT_THRESHOLD` from the cache.
+    fn clean_cache(&self) -> Result<()>;
+
+    /// Caches every submission of `user` that hasn't been announced yet, so that they never will be.
+    /// Returns how many submissions were settled.
+    fn settle_uncached_submissions(&self, user: &models::User) -> Result<usize> {
+        let mut settled = 0;
+        for submission in self.query_uncached_submissions(user)? {
+            if self.insert_cache_submission(&submission)? {
+                settled += 1;
+            }
+        }
+
+        Ok(settled)
+    }
+
+    /////*============== USER QUERIES ==============*/
+    /// Returns the user with the username: `username`, if they exist.
+    fn query_user(&self, username: &str) -> Result<Option<models::User>>;
+
+    /// Gathers all users tracked by at least one guild.
+    fn query_tracked_users(&self) -> Result<Vec<models::User>>;
+
+    /// Gathers all users tracked by `guild_id`.
+    fn query_guild_tracked_users(&self, guild_id: u64) -> Result<Vec<models::User>>;
+
+    /// Gathers every guild tracking `user`, along with the user's preferences in that guild.
+    fn query_tracking_guilds(&self, user: &models::User)
+        -> Result<Vec<(u64, models::UserPreferences)>>;
+
+    /// Inserts a user into the database.
+    fn insert_user(&self, user: &models::User) -> Result<()>;
+
+    /// Tracks a user in `guild_id`, inserting them if they aren't in the database already.
+    fn track_user(&self, guild_id: u64, user: &models::User) -> Result<()>;
+
+    /// Untracks a user in `guild_id`, inserting them if they aren't in the database already.
+    fn untrack_user(&self, guild_id: u64, user: &models::User) -> Result<()>;
+
+    /// Return whether a user is being tracked in `guild_id`.
+    fn is_tracked(&self, guild_id: u64, user: &models::User) -> Result<bool>;
+
+    /// Retrieves a user's preferences in `guild_id`.
+    ///
+    /// Returns None if the guild has no preferences for `user`.
+    fn query_user_preferences(&self, guild_id: u64, user: &models::User)
+        -> Result<Option<models::UserPreferences>>;
+
+    /// Updates a user's preferences in `guild_id`.
+    fn update_user_preferences(
+        &self,
+        guild_id: u64,
+        user: &models::User,
+        prefs: &models::UserPreferences,
+    ) -> Result<()>;
+
+    /// Inserts user's preferences in `guild_id`, doing nothing if they're already there.
+    /// Returns `true` if they were newly added, false otherwise.
+    fn insert_user_preferences(
+        &self,
+        guild_id: u64,
+        user: &models::User,
+        prefs: &models::UserPreferences,
+    ) -> Result<bool>;
+
+    /// Return whether a (tracked) user has completed a problem in the last day.
+    fn is_active(&self, user: &models::User) -> Result<bool>;
+
+    fn streak_increment(&self, user: &models::User) -> Result<()>;
+
+    fn query_streak(&self, user: &models::User) -> Result<u64>;
+
+    /// Breaks the user's streak.
+    fn streak_break(&self, user: &models::User) -> Result<()>;
+
+    /// Returns how many streak freezes the user has saved up.
+    fn query_streak_freezes(&self, user: &models::User) -> Result<u64>;
+
+    /// Sets how many streak freezes the user has saved up.
+    fn update_streak_freezes(&self, user: &models::User, freezes: u64) -> Result<()>;
+
+    /////*============== PROBLEM QUERIES ==============*/
+    /// Inserts the problem into Problems, or does nothing if it already is there.
+    /// Returns `true` if it was newly added, false otherwise.
+    fn insert_problem(&self, problem: &models::Problem) -> Result<bool>;
+
+    /// Returns the problem titled `title`, if it's in Problems.
+    fn query_problem(&self, title: &str) -> Result<Option<models::Problem>>;
+
+    /// Sets the difficulty of the problem titled `title`, e.g. once it's been looked up.
+    fn update_problem_difficulty(&self, title: &str, difficulty: &str) -> Result<()>;
+
+    /// Gathers (up to `limit`, alphabetically) the titles of problems containing `search`,
+    /// ignoring case.
+    fn query_problem_titles(&self, search: &str, limit: usize) -> Result<Vec<String>>;
+
+    /////*============== GUILD QUERIES ==============*/
+    /// Gathers the IDs of all guilds that have settings stored.
+    fn query_guilds(&self) -> Result<Vec<u64>>;
+
+    /// Retrieves a guild's settings, or the defaults if it has none stored.
+    fn query_guild_settings(&self, guild_id: u64) -> Result<models::GuildSettings>;
+
+    /// Inserts or replaces a guild's settings.
+    fn update_guild_settings(&self, guild_id: u64, settings: &models::GuildSettings)
+        -> Result<()>;
+
+    /// Moves preferences left over from before guilds were a thing into `guild_id`.
+    /// Returns how many users were moved.
+    fn claim_legacy_preferences(&self, guild_id: u64) -> Result<usize>;
+
+    /////*============== SEASON QUERIES ==============*/
+    /// Archives a season that's over, along with its final standings.
+    fn archive_season(&self, guild_id: u64, season: &models::Season) -> Result<()>;
+
+    /// Returns archived season `number` of `guild_id`, if there is one.
+    fn query_season(&self, guild_id: u64, number: u64) -> Result<Option<models::Season>>;
+
+    /////*============== CHALLENGE QUERIES ==============*/
+    /// Records a new challenge (its `id` is ignored), and returns the ID it was given.
+    fn insert_challenge(&self, challenge: &models::Challenge) -> Result<u64>;
+
+    /// Gathers every challenge that's still open, in every guild, soonest deadline first.
+    fn query_open_challenges(&self) -> Result<Vec<models::Challenge>>;
+
+    /// Settles challenge `id` as won or expired, at `resolved_at` (in milliseconds).
+    fn resolve_challenge(&self, id: u64, outcome: models::ChallengeOutcome, resolved_at: usize)
+        -> Result<()>;
+
+    /// Tallies the challenges `username` has won and lost in `guild_id`, as either side.
+    fn query_challenge_record(&self, guild_id: u64, username: &str)
+        -> Result<models::ChallengeRecord>;
+
+    /////*============== LIST QUERIES ==============*/
+    /// Stores a problem list in `guild_id`, replacing any list of the same name. Whoever followed
+    /// the old one follows the new one.
+    fn replace_problem_list(&self, guild_id: u64, list: &models::ProblemList) -> Result<()>;
+
+    /// Returns `guild_id`'s problem list called `name`, if it has one.
+    fn query_problem_list(&self, guild_id: u64, name: &str) -> Result<Option<models::ProblemList>>;
+
+    /// Gathers the names of `guild_id`'s problem lists, alphabetically.
+    fn query_problem_list_names(&self, guild_id: u64) -> Result<Vec<String>>;
+
+    /// Makes `username` follow (or stop following) `guild_id`'s problem list called `name`.
+    fn update_list_follow(&self, guild_id: u64, username: &str, name: &str, following: bool)
+        -> Result<()>;
+
+    /// Gathers the names of the problem lists `username` follows in `guild_id`, alphabetically.
+    fn query_followed_lists(&self, guild_id: u64, username: &str) -> Result<Vec<String>>;
+
+    /////*============== REMINDER QUERIES ==============*/
+    /// Inserts or replaces a member's streak reminder.
+    fn update_streak_reminder(&self, reminder: &models::StreakReminder) -> Result<()>;
+
+    /// Stops reminding a member about their streak.
+    fn delete_streak_reminder(&self, discord_id: u64) -> Result<()>;
+
+    /// Returns a member's streak reminder, if they have one.
+    fn query_streak_reminder(&self, discord_id: u64) -> Result<Option<models::StreakReminder>>;
+
+    /// Gathers every member's streak reminder.
+    fn query_streak_reminders(&self) -> Result<Vec<models::StreakReminder>>;
+
+    /////*============== KUDOS QUERIES ==============*/
+    /// Records a message announcing submissions, or does nothing if it already is recorded.
+    fn insert_submission_announcement(&self, announcement: &models::SubmissionAnnouncement)
+        -> Result<()>;
+
+    /// Returns the submission announcement sent as message `message_id`, if it is one.
+    fn query_submission_announcement(&self, message_id: u64)
+        -> Result<Option<models::SubmissionAnnouncement>>;
+
+    /// Records a reaction to a submission announcement, or does nothing if it already is recorded.
+    fn insert_kudos(&self, kudos: &models::Kudos) -> Result<()>;
+
+    /// Forgets a reaction to a submission announcement.
+    fn delete_kudos(&self, message_id: u64, giver_id: u64, emoji: &str) -> Result<()>;
+
+    /// Tallies the kudos every user got for their announcements in `guild_id` from reactions
+    /// added between `since` and `until` (in milliseconds), most kudos first.
+    fn query_kudos_totals(&self, guild_id: u64, since: usize, until: usize)
+        -> Result<Vec<(String, u64)>>;
+
+    /////*============== LINK QUERIES ==============*/
+    /// Links a Discord account to a LeetCode username, replacing any previous link.
+    /// The link stays verified only if it was already verified for the same username.
+    fn link_account(&self, discord_id: u64, username: &str) -> Result<()>;
+
+    /// Returns the LeetCode username linked to a Discord account, if any.
+    fn query_linked_username(&self, discord_id: u64) -> Result<Option<String>>;
+
+    /// Returns whether a Discord account has proven that it owns the LeetCode account `username`
+    /// (in any case: LeetCode usernames aren't case-sensitive).
+    fn is_verified_owner(&self, discord_id: u64, username: &str) -> Result<bool>;
+
+    /////*============== VERIFICATION QUERIES ==============*/
+    /// Starts verifying that a Discord account owns `username`, replacing any verification
+    /// already in progress for that account.
+    fn insert_verification(&self, discord_id: u64, username: &str, token: &str) -> Result<()>;
+
+    /// Returns the verification in progress for a Discord account, if it hasn't expired yet
+    /// (see `models::VERIFICATION_THRESHOLD`).
+    fn query_verification(&self, discord_id: u64) -> Result<Option<models::Verification>>;
+
+    /// Completes the verification in progress for a Discord account: the account is linked to the
+    /// verified username, and the token can't be used again.
+    fn complete_verification(&self, discord_id: u64) -> Result<()>;
+}
+
+/// A `Store` backed by a SQLite database file.
+pub struct SqliteStore {
+    path: String,
 }
 
-pub fn initialize_db() -> DBResult<()> {
-    // User table
-    log::info!("[initialize_db] creating Users table...");
-    connect()?.execute(
-        "CREATE TABLE IF NOT EXISTS Users (
-            username       TEXT        PRIMARY KEY,
-
-            easy_solved    INTEGER     NOT NULL,
-            medium_solved  INTEGER     NOT NULL,
-            hard_solved    INTEGER     NOT NULL,
-            total_solved   INTEGER     NOT NULL,
-
-            ranking        INTEGER     NOT NULL,
-            streak         INTEGER     NOT NULL
-        )",
-        [],
-    )?;
-
-    // Submission table
-    log::info!("[initialize_db] creating Submissions table...");
-    connect()?.execute(
-        "CREATE TABLE IF NOT EXISTS Submissions (
-            problem_name   TEXT        NOT NULL    REFERENCES Problems(problem_name),
-
-            username       TEXT        NOT NULL    REFERENCES Users(username),
-            language       TEXT        NOT NULL,
-            timestamp      TIMESTAMP   NOT NULL,
-            accepted       BOOLEAN     NOT NULL,
-
-            url TEXT        NOT NULL,
-
-            UNIQUE(problem_name, username, timestamp)
-        )",
-        [],
-    )?;
-
-    // Problem table
-    log::info!("[initialize_db] creating Problems table...");
-    connect()?.execute(
-        "CREATE TABLE IF NOT EXISTS Problems (
-            problem_name   TEXT        PRIMARY KEY,
-            problem_link   TEXT        NOT NULL,
-            difficulty     TEXT        NOT NULL,
-
-            UNIQUE(problem_name, problem_link, difficulty)
-        )",
-        [],
-    )?;
-
-    // Recent Submission Cache
-    log::info!("[initialize_db] creating RecentCache table...");
-    connect()?.execute(
-        "CREATE TABLE IF NOT EXISTS RecentCache (
-            problem_name   TEXT        NOT NULL    REFERENCES Problems(problem_name),
-            username       TEXT        NOT NULL    REFERENCES Users(username),
-            timestamp      TIMESTAMP   NOT NULL,
-            accepted       BOOLEAN     NOT NULL,
-
-            UNIQUE (problem_name, username, timestamp, accepted)
-        )",
-        [],
-    )?;
-
-    // UserPreferences
-    log::info!("[initialize_db] creating UserPrefs table...");
-    connect()?.execute(
-        "CREATE TABLE IF NOT EXISTS UserPrefs (
-            username          TEXT        NOT NULL    REFERENCES Users(username),
-
-            tracked           BOOLEAN     NOT NULL,
-            announce          BOOLEAN     NOT NULL,
-            announce_fail     BOOLEAN     NOT NULL,
-            announce_link     BOOLEAN     NOT NULL,
-
-            UNIQUE (username)
-        )",
-        [],
-    )?;
-
-    Ok(())
+impl SqliteStore {
+    pub fn new(path: &str) -> Self {
+        Self { path: path.to_owned() }
+    }
+
+    fn connect(&self) -> DBResult<Connection> {
+        Connection::open(&self.path)
+    }
+
+    pub fn initialize_db(&self) -> DBResult<()> {
+        // User table
+        log::info!("[initialize_db] creating Users table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Users (
+                username       TEXT        PRIMARY KEY,
+
+                easy_solved    INTEGER     NOT NULL,
+                medium_solved  INTEGER     NOT NULL,
+                hard_solved    INTEGER     NOT NULL,
+                total_solved   INTEGER     NOT NULL,
+
+                ranking        INTEGER     NOT NULL,
+                streak         INTEGER     NOT NULL,
+                freezes        INTEGER     NOT NULL    DEFAULT 0
+            )",
+            [],
+        )?;
+        self.add_column_if_missing("Users", "freezes", "INTEGER NOT NULL DEFAULT 0")?;
+
+        // Submission table
+        log::info!("[initialize_db] creating Submissions table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Submissions (
+                problem_name   TEXT        NOT NULL    REFERENCES Problems(problem_name),
+
+                username       TEXT        NOT NULL    REFERENCES Users(username),
+                language       TEXT        NOT NULL,
+                timestamp      TIMESTAMP   NOT NULL,
+                accepted       BOOLEAN     NOT NULL,
+
+                url TEXT        NOT NULL,
+
+                UNIQUE(problem_name, username, timestamp)
+            )",
+            [],
+        )?;
+
+        // Problem table
+        log::info!("[initialize_db] creating Problems table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Problems (
+                problem_name   TEXT        PRIMARY KEY,
+                problem_link   TEXT        NOT NULL,
+                difficulty     TEXT        NOT NULL,
+
+                UNIQUE(problem_name, problem_link, difficulty)
+            )",
+            [],
+        )?;
+
+        // Recent Submission Cache
+        log::info!("[initialize_db] creating RecentCache table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS RecentCache (
+                problem_name   TEXT        NOT NULL    REFERENCES Problems(problem_name),
+                username       TEXT        NOT NULL    REFERENCES Users(username),
+                timestamp      TIMESTAMP   NOT NULL,
+                accepted       BOOLEAN     NOT NULL,
+
+                UNIQUE (problem_name, username, timestamp, accepted)
+            )",
+            [],
+        )?;
+
+        // UserPreferences
+        // Before guilds, UserPrefs was keyed by username alone. Set those rows aside so they can
+        // be claimed by a guild later (see `claim_legacy_preferences`).
+        if self.table_exists("UserPrefs")? && !self.column_exists("UserPrefs", "guild_id")? {
+            log::warn!("[initialize_db] UserPrefs predates guilds, renaming it to UserPrefsLegacy...");
+            self.connect()?.execute("ALTER TABLE UserPrefs RENAME TO UserPrefsLegacy", [])?;
+        }
+
+        log::info!("[initialize_db] creating UserPrefs table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS UserPrefs (
+                guild_id          INTEGER     NOT NULL,
+                username          TEXT        NOT NULL    REFERENCES Users(username),
+
+                tracked           BOOLEAN     NOT NULL,
+                announce          BOOLEAN     NOT NULL,
+                announce_fail     BOOLEAN     NOT NULL,
+                announce_link     BOOLEAN     NOT NULL,
+                announce_digest   BOOLEAN     NOT NULL    DEFAULT 0,
+
+                UNIQUE (guild_id, username)
+            )",
+            [],
+        )?;
+        self.add_column_if_missing("UserPrefs", "announce_digest", "BOOLEAN NOT NULL DEFAULT 0")?;
+
+        // Guild settings
+        log::info!("[initialize_db] creating GuildSettings table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS GuildSettings (
+                guild_id                INTEGER     PRIMARY KEY,
+                announcements_channel   INTEGER,
+                admin_role              INTEGER,
+                prefix                  TEXT,
+                accepted_channel        INTEGER,
+                failures_channel        INTEGER,
+                streaks_channel         INTEGER,
+                updates_channel         INTEGER,
+                recaps_channel          INTEGER,
+                digest_window           INTEGER,
+                daily_recap             BOOLEAN     NOT NULL    DEFAULT 0,
+                weekly_recap            BOOLEAN     NOT NULL    DEFAULT 0,
+                recap_hour              INTEGER     NOT NULL    DEFAULT 0,
+                recap_day               INTEGER     NOT NULL    DEFAULT 0,
+                easy_points             INTEGER,
+                medium_points           INTEGER,
+                hard_points             INTEGER,
+                leaderboard_channel     INTEGER,
+                leaderboard_message     INTEGER,
+                leaderboard_period      TEXT,
+                seasons_channel         INTEGER,
+                season_length           TEXT,
+                season_number           INTEGER     NOT NULL    DEFAULT 0,
+                season_start            INTEGER,
+                challenges_channel      INTEGER,
+                goals_channel           INTEGER,
+                goal_count              INTEGER,
+                goal_difficulty         TEXT,
+                goal_milestone          INTEGER     NOT NULL    DEFAULT 0,
+                goal_milestone_at       INTEGER
+            )",
+            [],
+        )?;
+        self.add_column_if_missing("GuildSettings", "admin_role", "INTEGER")?;
+        self.add_column_if_missing("GuildSettings", "prefix", "TEXT")?;
+        let kind_channels = ["accepted_channel", "failures_channel", "streaks_channel",
+                             "updates_channel", "recaps_channel", "seasons_channel",
+                             "challenges_channel", "goals_channel"];
+        for column in kind_channels {
+            self.add_column_if_missing("GuildSettings", column, "INTEGER")?;
+        }
+        self.add_column_if_missing("GuildSettings", "digest_window", "INTEGER")?;
+        for column in ["daily_recap", "weekly_recap", "recap_hour", "recap_day"] {
+            self.add_column_if_missing("GuildSettings", column, "INTEGER NOT NULL DEFAULT 0")?;
+        }
+        for column in ["easy_points", "medium_points", "hard_points", "leaderboard_channel",
+                       "leaderboard_message"] {
+            self.add_column_if_missing("GuildSettings", column, "INTEGER")?;
+        }
+        self.add_column_if_missing("GuildSettings", "leaderboard_period", "TEXT")?;
+        self.add_column_if_missing("GuildSettings", "season_length", "TEXT")?;
+        self.add_column_if_missing("GuildSettings", "season_number", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("GuildSettings", "season_start", "INTEGER")?;
+        self.add_column_if_missing("GuildSettings", "goal_count", "INTEGER")?;
+        self.add_column_if_missing("GuildSettings", "goal_difficulty", "TEXT")?;
+        self.add_column_if_missing("GuildSettings", "goal_milestone", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("GuildSettings", "goal_milestone_at", "INTEGER")?;
+
+        // Discord account -> LeetCode username links
+        log::info!("[initialize_db] creating Links table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Links (
+                discord_id     INTEGER     PRIMARY KEY,
+                username       TEXT        NOT NULL,
+                verified       BOOLEAN     NOT NULL    DEFAULT 0
+            )",
+            [],
+        )?;
+        self.add_column_if_missing("Links", "verified", "BOOLEAN NOT NULL DEFAULT 0")?;
+
+        // Pending account verifications
+        log::info!("[initialize_db] creating Verifications table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Verifications (
+                discord_id     INTEGER     PRIMARY KEY,
+                username       TEXT        NOT NULL,
+                token          TEXT        NOT NULL,
+                issued_at      TIMESTAMP   NOT NULL
+            )",
+            [],
+        )?;
+
+        // Seasons that are over, and their final standings
+        log::info!("[initialize_db] creating Seasons table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Seasons (
+                guild_id       INTEGER     NOT NULL,
+                season         INTEGER     NOT NULL,
+                started_at     TIMESTAMP   NOT NULL,
+                ended_at       TIMESTAMP   NOT NULL,
+
+                UNIQUE (guild_id, season)
+            )",
+            [],
+        )?;
+
+        log::info!("[initialize_db] creating SeasonStandings table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS SeasonStandings (
+                guild_id       INTEGER     NOT NULL,
+                season         INTEGER     NOT NULL,
+                rank           INTEGER     NOT NULL,
+                username       TEXT        NOT NULL,
+                points         INTEGER     NOT NULL,
+                solved         INTEGER     NOT NULL,
+                reached_at     TIMESTAMP   NOT NULL,
+
+                UNIQUE (guild_id, season, username)
+            )",
+            [],
+        )?;
+
+        // Challenges between members, open or settled
+        log::info!("[initialize_db] creating Challenges table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Challenges (
+                challenge_id   INTEGER     PRIMARY KEY,
+                guild_id       INTEGER     NOT NULL,
+                challenger     TEXT        NOT NULL,
+                challenged     TEXT        NOT NULL,
+                problem_name   TEXT        NOT NULL,
+                issued_at      TIMESTAMP   NOT NULL,
+                deadline       TIMESTAMP   NOT NULL,
+                outcome        TEXT        NOT NULL    DEFAULT 'open',
+                resolved_at    TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Problem lists, one row per problem, and who follows them
+        log::info!("[initialize_db] creating ProblemLists table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS ProblemLists (
+                guild_id       INTEGER     NOT NULL,
+                list_name      TEXT        NOT NULL,
+                position       INTEGER     NOT NULL,
+                problem_name   TEXT        NOT NULL,
+
+                UNIQUE (guild_id, list_name, problem_name)
+            )",
+            [],
+        )?;
+
+        log::info!("[initialize_db] creating ListFollows table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS ListFollows (
+                guild_id       INTEGER     NOT NULL,
+                username       TEXT        NOT NULL,
+                list_name      TEXT        NOT NULL,
+
+                UNIQUE (guild_id, username, list_name)
+            )",
+            [],
+        )?;
+
+        // Members who asked to be reminded before losing their streak
+        log::info!("[initialize_db] creating StreakReminders table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS StreakReminders (
+                discord_id     INTEGER     PRIMARY KEY,
+                hours_before   INTEGER     NOT NULL,
+                channel_id     INTEGER
+            )",
+            [],
+        )?;
+
+        // Messages announcing submissions, so that reactions to them count as kudos
+        log::info!("[initialize_db] creating SubmissionAnnouncements table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS SubmissionAnnouncements (
+                message_id     INTEGER     PRIMARY KEY,
+                guild_id       INTEGER     NOT NULL,
+                username       TEXT        NOT NULL
+            )",
+            [],
+        )?;
+
+        // Reactions to submission announcements
+        log::info!("[initialize_db] creating Kudos table...");
+        self.connect()?.execute(
+            "CREATE TABLE IF NOT EXISTS Kudos (
+                message_id     INTEGER     NOT NULL,
+                giver_id       INTEGER     NOT NULL,
+                emoji          TEXT        NOT NULL,
+                given_at       INTEGER     NOT NULL,
+
+                UNIQUE (message_id, giver_id, emoji)
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// [internal] Adds `column` to `table` for databases created before it existed.
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> DBResult<()> {
+        if !self.column_exists(table, column)? {
+            log::info!("[initialize_db] adding {column} to {table}...");
+            self.connect()?
+                .execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"), [])?;
+        }
+
+        Ok(())
+    }
+
+    /// [internal] Checks if `table` exists in the database.
+    fn table_exists(&self, table: &str) -> DBResult<bool> {
+        self.connect()?
+            .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?")?
+            .exists(params![table])
+    }
+
+    /// [internal] Checks if `table` has a column named `column`.
+    fn column_exists(&self, table: &str, column: &str) -> DBResult<bool> {
+        self.connect()?
+            .prepare("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")?
+            .exists(params![table, column])
+    }
+
+    /// [internal] Checks if the user is in the database.
+    fn user_exists(&self, user: &models::User) -> DBResult<bool> {
+        let connection = self.connect()?;
+        connection
+            .prepare("SELECT * FROM Users WHERE username = :username")?
+            .exists(rusqlite::named_params!{ ":username": user.username })
+    }
 }
 
 /////*============== SUBMISSION QUERIES ==============*/
-impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::Submission {
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::Problem {
     type Error = rusqlite::Error;
 
     fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
-        let problem = models::Problem {
+        Ok(Self {
             title: row.get("problem_name")?,
             url: row.get("problem_link")?,
             difficulty: row.get("difficulty")?,
-        };
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::Submission {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        let problem = models::Problem::try_from(row)?;
 
         Ok(Self {
             problem,
@@ -117,180 +631,6 @@ impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::Submission {
     }
 }
 
-/// Gathers all recent submissions for a user.
-pub fn query_submissions_recent_all(user: &models::User) -> DBResult<Vec<models::Submission>> {
-    let connection = connect()?;
-    let username = &user.username;
-
-    // Get the current timestamp, approximately
-    let current_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards...?")
-        .as_millis() as usize;
-
-    // Parameters for our query. We're mainly trying to only grab submissions
-    // that have been posted in the last `models::RECENT_THRESHOLD` milliseconds.
-    let query_params = rusqlite::named_params! {
-            ":username": username,
-            ":current_timestamp": current_timestamp,
-            ":recent_threshold": models::RECENT_THRESHOLD
-    };
-
-    // Preparation for the query.
-    let mut stmt = connection.prepare(
-        "SELECT s.username, s.timestamp, s.accepted,
-                    p.problem_name, p.problem_link, p.difficulty
-         FROM Submissions s
-         JOIN Problems p ON s.problem_name = p.problem_name
-         WHERE s.username = :username
-           AND :current_timestamp - s.timestamp < :recent_threshold
-         ORDER BY s.timestamp DESC",
-    )?;
-
-    // Query!
-    // PERFORMANCE CONSIDERATION:
-    //   We eagerly evaluate the iterator into a vector, here. Though it doesn't really make sense
-    //   for us to really need _all_
-    let submissions = stmt
-        .query_map(query_params, |row| models::Submission::try_from(row))?
-        .collect::<DBResult<Vec<models::Submission>>>()?;
-
-    Ok(submissions)
-}
-
-/// Inserts a Submission into the database.
-/// Returns `true` if it was newly added, false otherwise.
-pub fn insert_submission(submission: &models::Submission) -> DBResult<bool> {
-    let connection = connect()?;
-
-    log::trace!("[insert_submission] Inserting submission for {} into Submissions...",
-        submission.problem.title);
-
-    let query_params = rusqlite::named_params! {
-            ":problem_name":   submission.problem.title,
-            ":username":       submission.username,
-            ":language":       submission.language,
-            ":timestamp":      submission.timestamp,
-            ":accepted":       submission.accepted,
-            ":url":            submission.url,
-    };
-
-    connection
-        .prepare(
-            "INSERT INTO Submissions 
-                ( problem_name,  username,  language,  timestamp,  accepted,  url)
-            VALUES 
-                (:problem_name, :username, :language, :timestamp, :accepted, :url)"
-        )?
-        .execute(query_params)
-        .map_or_else(swallow_constraint_violation, |_| Ok(true))
-}
-
-/////*============== RECENT CACHE QUERIES ==============*/
-/// Queries the database for submissions that haven't already been announced to the server.
-pub fn query_uncached_submissions(user: &models::User) -> DBResult<Vec<models::Submission>> {
-    let connection = connect()?;
-    log::trace!("[query_uncached_submissions] Querying {} for uncached submissions...",
-                 user.username);
-
-    // Get the current timestamp, approximately
-    let current_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards????")
-        .as_millis() as usize;
-
-    // Parameters for our query. We're mainly trying to only grab submissions
-    // that have been posted in the last `models::RECENT_THRESHOLD` seconds.
-    let query_params = rusqlite::named_params! {
-            ":username": &user.username,
-            ":current_timestamp": current_timestamp,
-            ":recent_threshold": models::RECENT_THRESHOLD
-    };
-
-    // Preparation for the query.
-    let mut stmt = connection.prepare(
-        "SELECT s.*,
-                    p.problem_name, p.problem_link, p.difficulty
-             FROM Submissions s
-             JOIN Problems p ON s.problem_name = p.problem_name
-             WHERE s.username = :username
-               and :current_timestamp - s.timestamp < :recent_threshold
-               and NOT EXISTS (
-                 SELECT 1 
-                 FROM RecentCache r 
-                 WHERE r.timestamp = s.timestamp
-                   and r.username = s.username
-               )
-             ORDER BY s.timestamp DESC",
-    )?;
-
-    let submissions = stmt
-        .query_map(query_params, |row| {
-            models::Submission::try_from(row)
-                .inspect(|sub| 
-                    log::trace!("[query_uncached_submissions] Found uncached submission: {sub}"))
-                .inspect_err(|err| 
-                    log::error!("[query_uncached_submissions] Could not convert row into \
-                                 submission: {err}"))
-            }
-        )?
-        .collect::<DBResult<Vec<models::Submission>>>()?;
-
-    Ok(submissions)
-}
-
-/// Adds the (problem, user) entry into the recent cache if it doesn't exist.
-/// Returns `true` if it was newly added, false otherwise.
-pub fn insert_cache_submission(submission: &models::Submission) -> DBResult<bool> {
-    log::trace!("[insert_cache_submission] Inserting submission into the cache.");
-    let connection = connect()?;
-
-    let query_params = rusqlite::named_params! {
-            ":username": &submission.username,
-            ":problem_name": &submission.problem.title,
-            ":timestamp": &submission.timestamp,
-            ":accepted": &submission.accepted,
-    };
-
-    // Preparation for the query.
-    connection
-        .prepare(
-            "INSERT INTO RecentCache (username, problem_name, timestamp, accepted)
-             VALUES (:username, :problem_name, :timestamp, :accepted)",
-        )?
-        .execute(query_params)
-        .map_or_else(swallow_constraint_violation, |_| Ok(true))
-}
-
-/// Cleans the cache and returns the removed submissions.
-pub fn clean_cache() -> Result<()> {
-    log::trace!("[clean_cache] Clearing the cache.");
-    let connection = connect()?;
-
-    // Get the current timestamp, approximately
-    let current_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .context("Time went backwards????")?
-        .as_millis() as usize;
-
-    // Parameters for our query. We're mainly trying to only grab submissions
-    // that have been posted in the last `models::RECENT_THRESHOLD` milliseconds.
-    let query_params = rusqlite::named_params! {
-            ":current_timestamp": current_timestamp,
-            ":recent_threshold": models::RECENT_THRESHOLD
-    };
-
-    // Preparation for the query.
-    connection
-        .prepare(
-            "DELETE FROM RecentCache
-             WHERE :current_timestamp - timestamp > :recent_threshold",
-        )?
-        .execute(query_params)?;
-
-    Ok(())
-}
-
 /////*============== USER QUERIES ==============*/
 impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::User {
     type Error = rusqlite::Error;
@@ -318,292 +658,1294 @@ impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::UserPreferences {
                 .then(|| AnnouncementPreferences {
                             announce_failures: row.get("announce_fail").unwrap_or(false),
                             has_submission_link: row.get("announce_link").unwrap_or(false),
+                            digest: row.get("announce_digest").unwrap_or(false),
                          }
                 )
         })
     }
 }
 
-/// Returns the user with the username: `username`, if they exist.
-pub fn query_user(username: &str) -> DBResult<Option<models::User>> {
-    let connection = connect()?;
+/////*============== GUILD QUERIES ==============*/
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::GuildSettings {
+    type Error = rusqlite::Error;
 
-    connection
-        .prepare("SELECT * FROM Users WHERE username = :username")?
-        .query(rusqlite::named_params! { ":username": username })?
-        .next()?
-        .map(|x| x.try_into())
-        .transpose()
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            announcements_channel: row.get("announcements_channel")?,
+            admin_role: row.get("admin_role")?,
+            prefix: row.get("prefix")?,
+            accepted_channel: row.get("accepted_channel")?,
+            failures_channel: row.get("failures_channel")?,
+            streaks_channel: row.get("streaks_channel")?,
+            updates_channel: row.get("updates_channel")?,
+            recaps_channel: row.get("recaps_channel")?,
+            digest_window: row.get("digest_window")?,
+            daily_recap: row.get("daily_recap")?,
+            weekly_recap: row.get("weekly_recap")?,
+            recap_hour: row.get("recap_hour")?,
+            recap_day: row.get("recap_day")?,
+            easy_points: row.get("easy_points")?,
+            medium_points: row.get("medium_points")?,
+            hard_points: row.get("hard_points")?,
+            leaderboard_channel: row.get("leaderboard_channel")?,
+            leaderboard_message: row.get("leaderboard_message")?,
+            leaderboard_period: row.get("leaderboard_period")?,
+            seasons_channel: row.get("seasons_channel")?,
+            season_length: row.get("season_length")?,
+            season_number: row.get("season_number")?,
+            season_start: row.get("season_start")?,
+            challenges_channel: row.get("challenges_channel")?,
+            goals_channel: row.get("goals_channel")?,
+            goal_count: row.get("goal_count")?,
+            goal_difficulty: row.get("goal_difficulty")?,
+            goal_milestone: row.get("goal_milestone")?,
+            goal_milestone_at: row.get("goal_milestone_at")?,
+        })
+    }
 }
 
-/// Gathers all tracked users.
-pub fn query_tracked_users() -> DBResult<Vec<models::User>> {
-    log::trace!("[query_tracked_users)] Querying all tracked users.");
-    let connection = connect()?;
-
-    // Preparation for the query.
-    let mut stmt = connection.prepare(
-        "SELECT u.username, u.easy_solved, u.medium_solved, u.hard_solved,
-                u.total_solved, u.ranking, u.streak
-         FROM Users u
-         JOIN UserPrefs p ON u.username = p.username
-         WHERE p.tracked = 1",
-    )?;
-
-    // Query!
-    let submissions = stmt
-        .query_map([], |row| models::User::try_from(row))?
-        .collect::<Result<Vec<models::User>, _>>()?;
-
-    Ok(submissions)
+/////*============== SEASON QUERIES ==============*/
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::LeaderboardEntry {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            username: row.get("username")?,
+            points: row.get("points")?,
+            solved: row.get("solved")?,
+            reached_at: row.get("reached_at")?,
+        })
+    }
 }
 
-pub fn insert_user(user: &models::User, prefs: &models::UserPreferences) -> DBResult<()> {
-    let connection = connect()?;
-
-    log::trace!(
-        "[insert_user] Inserting user {} into Users...",
-        user.username
-    );
-
-    let query_params = rusqlite::named_params! {
-            ":username":      user.username,
-            ":easy_solved":   user.easy_solved,
-            ":medium_solved": user.medium_solved,
-            ":hard_solved":   user.hard_solved,
-            ":total_solved":  user.total_solved,
-            ":ranking":       user.ranking,
-            ":streak":        user.streak,
-    };
-
-    connection.prepare(
-        "INSERT INTO Users ( username,  easy_solved,  medium_solved,  hard_solved,
-                             total_solved,  ranking,  streak)
-         VALUES            (:username, :easy_solved, :medium_solved, :hard_solved,
-                            :total_solved, :ranking, :streak)"
-    )?.execute(query_params)?;
-
-    log::info!("User {} has been added to the database.", user.username);
-
-    insert_user_preferences(user, prefs)?;
-    log::info!("User preferences for {} have been initialized.", user.username);
-
-    Ok(())
+/////*============== CHALLENGE QUERIES ==============*/
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::Challenge {
+    type Error = rusqlite::Error;
+
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        let outcome = row.get::<_, String>("outcome")?;
+        Ok(Self {
+            id: row.get("challenge_id")?,
+            guild_id: row.get("guild_id")?,
+            challenger: row.get("challenger")?,
+            challenged: row.get("challenged")?,
+            problem: row.get("problem_name")?,
+            issued_at: row.get("issued_at")?,
+            deadline: row.get("deadline")?,
+            outcome: models::ChallengeOutcome::from_name(&outcome)
+                .unwrap_or(models::ChallengeOutcome::Open),
+            resolved_at: row.get("resolved_at")?,
+        })
+    }
 }
 
-/// Tracks a user by updating the "tracked" field in UserPrefs to true.
-///   Inserts the user if it isn't in the database already.
-pub fn track_user(user: &models::User) -> DBResult<()> {
-    let username = &user.username;
-    log::trace!("[track_user] Tracking user {}...", username);
+/////*============== REMINDER QUERIES ==============*/
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::StreakReminder {
+    type Error = rusqlite::Error;
 
-    if !user_exists(user)? {
-        log::trace!("[track_user] User '{}' does not already exist, adding to database.", username);
-        insert_user(user, &models::DEFAULT_USER_PREFERENCES)?;
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            discord_id: row.get("discord_id")?,
+            hours_before: row.get("hours_before")?,
+            channel_id: row.get("channel_id")?,
+        })
     }
+}
+
+/////*============== KUDOS QUERIES ==============*/
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::SubmissionAnnouncement {
+    type Error = rusqlite::Error;
 
-    if query_user_preferences(user)?.is_none() {
-        insert_user_preferences(user, &models::DEFAULT_USER_PREFERENCES)?;
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            message_id: row.get("message_id")?,
+            guild_id: row.get("guild_id")?,
+            username: row.get("username")?,
+        })
     }
+}
 
-    let connection = connect()?;
-    connection
-        .prepare("UPDATE UserPrefs SET tracked = 1 WHERE username = :username")?
-        .execute(rusqlite::named_params! { ":username": username, })
-        .inspect_err(|err| log::error!("[track_user] Error tracking user '{username}': {err}"))?;
+/////*============== VERIFICATION QUERIES ==============*/
+impl<'a> TryFrom<&'a rusqlite::Row<'a>> for models::Verification {
+    type Error = rusqlite::Error;
 
-    Ok(())
+    fn try_from(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            username: row.get("username")?,
+            token: row.get("token")?,
+            issued_at: row.get("issued_at")?,
+        })
+    }
 }
 
-/// Untracks a user by updating the "tracked" field to false.
-///   Inserts the user if it isn't in the database already.
-pub fn untrack_user(user: &models::User) -> DBResult<()> {
-    if !user_exists(user)? {
-        insert_user(user, &models::DEFAULT_USER_PREFERENCES)?;
+impl Store for SqliteStore {
+    /////*============== SUBMISSION QUERIES ==============*/
+    fn query_submissions_recent_all(&self, user: &models::User) -> Result<Vec<models::Submission>> {
+        let connection = self.connect()?;
+        let username = &user.username;
+
+        // Get the current timestamp, approximately
+        let current_timestamp = current_timestamp();
+
+        // Parameters for our query. We're mainly trying to only grab submissions
+        // that have been posted in the last `models::RECENT_THRESHOLD` milliseconds.
+        let query_params = rusqlite::named_params! {
+                ":username": username,
+                ":current_timestamp": current_timestamp,
+                ":recent_threshold": models::RECENT_THRESHOLD
+        };
+
+        // Preparation for the query.
+        let mut stmt = connection.prepare(
+            "SELECT s.*,
+                    p.problem_name, p.problem_link, p.difficulty
+             FROM Submissions s
+             JOIN Problems p ON s.problem_name = p.problem_name
+             WHERE s.username = :username
+               AND :current_timestamp - s.timestamp < :recent_threshold
+             ORDER BY s.timestamp DESC",
+        )?;
+
+        // Query!
+        // PERFORMANCE CONSIDERATION:
+        //   We eagerly evaluate the iterator into a vector, here. Though it doesn't really make sense
+        //   for us to really need _all_
+        let submissions = stmt
+            .query_map(query_params, |row| models::Submission::try_from(row))?
+            .collect::<DBResult<Vec<models::Submission>>>()?;
+
+        Ok(submissions)
     }
 
-    let connection = connect()?;
-    connection
-        .prepare("UPDATE UserPrefs SET tracked = 0 WHERE username = :username")?
-        .execute(rusqlite::named_params! { ":username": user.username, })?;
+    fn query_submissions_between(&self, user: &models::User, since: usize, until: usize)
+        -> Result<Vec<models::Submission>>
+    {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! {
+                ":username": user.username,
+                ":since":    since,
+                ":until":    until,
+        };
 
-    Ok(())
-}
+        let submissions = connection
+            .prepare(
+                "SELECT s.*,
+                        p.problem_name, p.problem_link, p.difficulty
+                 FROM Submissions s
+                 JOIN Problems p ON s.problem_name = p.problem_name
+                 WHERE s.username = :username
+                   AND s.timestamp >= :since
+                   AND s.timestamp < :until
+                 ORDER BY s.timestamp DESC",
+            )?
+            .query_map(query_params, |row| models::Submission::try_from(row))?
+            .collect::<DBResult<Vec<models::Submission>>>()?;
+
+        Ok(submissions)
+    }
 
-/// Return whether a user is being tracked.
-pub fn is_tracked(user: &models::User) -> DBResult<bool> {
-    let connection = connect()?;
-    connection
-        .prepare("SELECT * FROM UserPrefs WHERE username = :username AND tracked = 1")?
-        .exists(rusqlite::named_params! { ":username": user.username, })
-}
+    fn query_solved_before(&self, user: &models::User, before: usize) -> Result<Vec<String>> {
+        let connection = self.connect()?;
+        let solved = connection
+            .prepare(
+                "SELECT DISTINCT problem_name FROM Submissions
+                 WHERE username = :username
+                   AND accepted = 1
+                   AND timestamp < :before",
+            )?
+            .query_map(
+                rusqlite::named_params! { ":username": user.username, ":before": before },
+                |row| row.get("problem_name"),
+            )?
+            .collect::<DBResult<Vec<String>>>()?;
+
+        Ok(solved)
+    }
 
-/// Retrieves a user's preferences from the database.
-///
-/// Returns None if no such `user` is in the database.
-pub fn query_user_preferences(user: &models::User) -> DBResult<Option<models::UserPreferences>> {
-    let connection = connect()?;
-    connection
-        .prepare("SELECT * FROM UserPrefs WHERE username = :username")?
-        .query(rusqlite::named_params! { ":username": user.username })?
-        .next()?
-        .map(|row| row.try_into())
-        .transpose()
-}
+    fn insert_submission(&self, submission: &models::Submission) -> Result<bool> {
+        let connection = self.connect()?;
 
-/// Updates a user's preferences into the database.
-pub fn update_user_preferences(
-    user: &models::User,
-    prefs: &models::UserPreferences
-) -> DBResult<()>
-{
-    let connection = connect()?;
-    let query_params = rusqlite::named_params! {
-            ":username":      user.username,
-            ":tracked":       prefs.tracked,
-            ":announce":      prefs.announcement.is_some(),
-            ":announce_fail": prefs.announcement.as_ref().is_some_and(|a| a.announce_failures),
-            ":announce_link": prefs.announcement.as_ref().is_some_and(|a| a.has_submission_link)
-    };
-
-    connection
-        .prepare(
-            "UPDATE UserPrefs SET
-                tracked = :tracked,
-                announce = :announce,
-                announce_fail = :announce_fail,
-                announce_link = :announce_link
-             WHERE username = :username"
-        )?
-        .execute(query_params)
-        .inspect_err(|err| log::error!("[update_user_preferences] Could not update user \
-                                        preferences: {err}"))?;
-
-    Ok(())
-}
+        log::trace!("[insert_submission] Inserting submission for {} into Submissions...",
+            submission.problem.title);
 
-/// Inserts user's preferences into the database, doing nothing if they're already there.
-pub fn insert_user_preferences(
-    user: &models::User,
-    prefs: &models::UserPreferences
-) -> DBResult<bool>
-{
-    let connection = connect()?;
-    let query_params = rusqlite::named_params! {
-            ":username":      user.username,
-            ":tracked":       prefs.tracked,
-            ":announce":      prefs.announcement.is_some(),
-            ":announce_fail": prefs.announcement.as_ref().is_some_and(|a| a.announce_failures),
-            ":announce_link": prefs.announcement.as_ref().is_some_and(|a| a.has_submission_link)
-    };
-
-    connection
-        .prepare(
-            "INSERT INTO UserPrefs (username,   tracked,  announce,  announce_fail,  announce_link)
-             VALUES                (:username, :tracked, :announce, :announce_fail, :announce_link)"
-        )?
-        .execute(query_params)
-        .map_or_else(swallow_constraint_violation, |_| Ok(true))
-}
+        let query_params = rusqlite::named_params! {
+                ":problem_name":   submission.problem.title,
+                ":username":       submission.username,
+                ":language":       submission.language,
+                ":timestamp":      submission.timestamp,
+                ":accepted":       submission.accepted,
+                ":url":            submission.url,
+        };
 
+        Ok(connection
+            .prepare(
+                "INSERT INTO Submissions
+                    ( problem_name,  username,  language,  timestamp,  accepted,  url)
+                VALUES
+                    (:problem_name, :username, :language, :timestamp, :accepted, :url)"
+            )?
+            .execute(query_params)
+            .map_or_else(swallow_constraint_violation, |_| Ok(true))?)
+    }
+
+    fn insert_fake_submission(
+        &self,
+        user: &models::User,
+        problem_name: String,
+        accepted: bool,
+        timestamp: usize,
+    ) -> Result<()> {
+        let problem = models::Problem {
+            title: problem_name,
+            url: String::from("no_url"),
+            difficulty: String::from("no difficulty"),
+        };
 
-/// Return whether a user has completed a problem in the last day.
-pub fn is_active(user: &models::User) -> DBResult<bool> {
-    let connection = connect()?;
+        self.insert_problem(&problem)?;
 
-    // Get the current timestamp, approximately
-    let current_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards????")
-        .as_millis() as usize;
-
-    const DAY_IN_MILLIS: u64 = 86_400_000;
-    let query_params = rusqlite::named_params! {
-            ":username":      user.username,
-            ":current_timestamp": current_timestamp,
-            ":DAY_IN_MILLIS": DAY_IN_MILLIS,
-    };
-
-    let is_tracked = connection
-        .prepare(
-            "SELECT 1
+        let submission = models::Submission {
+            username: user.username.to_owned(),
+            problem,
+            language: String::from("no_language"),
+            timestamp,
+            accepted,
+            url: String::from("no_url"),
+        };
+
+        self.insert_submission(&submission)?;
+
+        Ok(())
+    }
+
+    /////*============== RECENT CACHE QUERIES ==============*/
+    fn query_uncached_submissions(&self, user: &models::User) -> Result<Vec<models::Submission>> {
+        let connection = self.connect()?;
+        log::trace!("[query_uncached_submissions] Querying {} for uncached submissions...",
+                     user.username);
+
+        // Get the current timestamp, approximately
+        let current_timestamp = current_timestamp();
+
+        // Parameters for our query. We're mainly trying to only grab submissions
+        // that have been posted in the last `models::RECENT_THRESHOLD` seconds.
+        let query_params = rusqlite::named_params! {
+                ":username": &user.username,
+                ":current_timestamp": current_timestamp,
+                ":recent_threshold": models::RECENT_THRESHOLD
+        };
+
+        // Preparation for the query.
+        let mut stmt = connection.prepare(
+            "SELECT s.*,
+                        p.problem_name, p.problem_link, p.difficulty
+                 FROM Submissions s
+                 JOIN Problems p ON s.problem_name = p.problem_name
+                 WHERE s.username = :username
+                   and :current_timestamp - s.timestamp < :recent_threshold
+                   and NOT EXISTS (
+                     SELECT 1
+                     FROM RecentCache r
+                     WHERE r.timestamp = s.timestamp
+                       and r.username = s.username
+                   )
+                 ORDER BY s.timestamp DESC",
+        )?;
+
+        let submissions = stmt
+            .query_map(query_params, |row| {
+                models::Submission::try_from(row)
+                    .inspect(|sub|
+                        log::trace!("[query_uncached_submissions] Found uncached submission: {sub}"))
+                    .inspect_err(|err|
+                        log::error!("[query_uncached_submissions] Could not convert row into \
+                                     submission: {err}"))
+                }
+            )?
+            .collect::<DBResult<Vec<models::Submission>>>()?;
+
+        Ok(submissions)
+    }
+
+    fn insert_cache_submission(&self, submission: &models::Submission) -> Result<bool> {
+        log::trace!("[insert_cache_submission] Inserting submission into the cache.");
+        let connection = self.connect()?;
+
+        let query_params = rusqlite::named_params! {
+                ":username": &submission.username,
+                ":problem_name": &submission.problem.title,
+                ":timestamp": &submission.timestamp,
+                ":accepted": &submission.accepted,
+        };
+
+        // Preparation for the query.
+        Ok(connection
+            .prepare(
+                "INSERT INTO RecentCache (username, problem_name, timestamp, accepted)
+                 VALUES (:username, :problem_name, :timestamp, :accepted)",
+            )?
+            .execute(query_params)
+            .map_or_else(swallow_constraint_violation, |_| Ok(true))?)
+    }
+
+    fn clean_cache(&self) -> Result<()> {
+        log::trace!("[clean_cache] Clearing the cache.");
+        let connection = self.connect()?;
+
+        // Get the current timestamp, approximately
+        let current_timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("Time went backwards????")?
+            .as_millis() as usize;
+
+        // Parameters for our query. We're mainly trying to only grab submissions
+        // that have been posted in the last `models::RECENT_THRESHOLD` milliseconds.
+        let query_params = rusqlite::named_params! {
+                ":current_timestamp": current_timestamp,
+                ":recent_threshold": models::RECENT_THRESHOLD
+        };
+
+        // Preparation for the query.
+        connection
+            .prepare(
+                "DELETE FROM RecentCache
+                 WHERE :current_timestamp - timestamp > :recent_threshold",
+            )?
+            .execute(query_params)?;
+
+        Ok(())
+    }
+
+    /////*============== USER QUERIES ==============*/
+    fn query_user(&self, username: &str) -> Result<Option<models::User>> {
+        let connection = self.connect()?;
+
+        Ok(connection
+            .prepare("SELECT * FROM Users WHERE username = :username")?
+            .query(rusqlite::named_params! { ":username": username })?
+            .next()?
+            .map(|x| x.try_into())
+            .transpose()?)
+    }
+
+    fn query_tracked_users(&self) -> Result<Vec<models::User>> {
+        log::trace!("[query_tracked_users)] Querying all tracked users.");
+        let connection = self.connect()?;
+
+        // Preparation for the query.
+        let mut stmt = connection.prepare(
+            "SELECT DISTINCT u.username, u.easy_solved, u.medium_solved, u.hard_solved,
+                    u.total_solved, u.ranking, u.streak
              FROM Users u
-             JOIN UserPrefs   p ON p.username = u.username
-             JOIN Submissions s ON s.username = u.username
-             WHERE u.username = :username
-               and p.tracked = 1
-               and s.accepted = 1
-               and :current_timestamp - s.timestamp < :DAY_IN_MILLIS",
-        )?
-        .exists(query_params)
-        .inspect_err(|err| log::error!("[is_active] Could not check if user was active: {err}"))?;
-
-    Ok(is_tracked)
-}
+             JOIN UserPrefs p ON u.username = p.username
+             WHERE p.tracked = 1",
+        )?;
 
-pub fn streak_increment(user: &models::User) -> DBResult<()> {
-    let connection = connect()?;
-    connection
-        .prepare(
-            "UPDATE Users 
-                         SET streak = streak + 1 
-                         WHERE username = ?",
-        )?
-        .execute(params![&user.username])?;
-
-    Ok(())
-}
+        // Query!
+        let submissions = stmt
+            .query_map([], |row| models::User::try_from(row))?
+            .collect::<Result<Vec<models::User>, _>>()?;
 
-pub fn query_streak(user: &models::User) -> DBResult<u64> {
-    log::trace!("[query_streak] Querying streak for {}...", user.username);
-    let connection = connect()?;
-    let mut stmt = connection.prepare("SELECT streak FROM Users WHERE username = ?")?;
-    stmt.query_row(params![&user.username], |row| row.get("streak"))
-}
+        Ok(submissions)
+    }
 
-// Breaks the user's streak.
-pub fn streak_break(user: &models::User) -> DBResult<()> {
-    let connection = connect()?;
-    connection
-        .prepare("UPDATE Users SET streak = 0 WHERE username = ?")?
-        .execute(params![&user.username])?;
+    fn query_guild_tracked_users(&self, guild_id: u64) -> Result<Vec<models::User>> {
+        log::trace!("[query_guild_tracked_users] Querying users tracked by guild {guild_id}.");
+        let connection = self.connect()?;
 
-    Ok(())
-}
+        let mut stmt = connection.prepare(
+            "SELECT u.username, u.easy_solved, u.medium_solved, u.hard_solved,
+                    u.total_solved, u.ranking, u.streak
+             FROM Users u
+             JOIN UserPrefs p ON u.username = p.username
+             WHERE p.tracked = 1
+               AND p.guild_id = :guild_id",
+        )?;
+
+        let users = stmt
+            .query_map(rusqlite::named_params! { ":guild_id": guild_id },
+                       |row| models::User::try_from(row))?
+            .collect::<Result<Vec<models::User>, _>>()?;
+
+        Ok(users)
+    }
+
+    fn query_tracking_guilds(&self, user: &models::User)
+        -> Result<Vec<(u64, models::UserPreferences)>>
+    {
+        let connection = self.connect()?;
+
+        let mut stmt = connection.prepare(
+            "SELECT * FROM UserPrefs WHERE username = :username AND tracked = 1",
+        )?;
+
+        let guilds = stmt
+            .query_map(rusqlite::named_params! { ":username": user.username }, |row| {
+                Ok((row.get("guild_id")?, models::UserPreferences::try_from(row)?))
+            })?
+            .collect::<DBResult<Vec<_>>>()?;
+
+        Ok(guilds)
+    }
+
+    fn insert_user(&self, user: &models::User) -> Result<()> {
+        let connection = self.connect()?;
+
+        log::trace!(
+            "[insert_user] Inserting user {} into Users...",
+            user.username
+        );
+
+        let query_params = rusqlite::named_params! {
+                ":username":      user.username,
+                ":easy_solved":   user.easy_solved,
+                ":medium_solved": user.medium_solved,
+                ":hard_solved":   user.hard_solved,
+                ":total_solved":  user.total_solved,
+                ":ranking":       user.ranking,
+                ":streak":        user.streak,
+        };
+
+        connection.prepare(
+            "INSERT INTO Users ( username,  easy_solved,  medium_solved,  hard_solved,
+                                 total_solved,  ranking,  streak)
+             VALUES            (:username, :easy_solved, :medium_solved, :hard_solved,
+                                :total_solved, :ranking, :streak)"
+        )?.execute(query_params)?;
+
+        log::info!("User {} has been added to the database.", user.username);
+
+        Ok(())
+    }
+
+    fn track_user(&self, guild_id: u64, user: &models::User) -> Result<()> {
+        let username = &user.username;
+        log::trace!("[track_user] Tracking user {} in guild {}...", username, guild_id);
+
+        if !self.user_exists(user)? {
+            log::trace!("[track_user] User '{}' does not already exist, adding to database.",
+                        username);
+            self.insert_user(user)?;
+        }
+
+        if self.query_user_preferences(guild_id, user)?.is_none() {
+            self.insert_user_preferences(guild_id, user, &models::DEFAULT_USER_PREFERENCES)?;
+        }
+
+        let connection = self.connect()?;
+        connection
+            .prepare("UPDATE UserPrefs SET tracked = 1
+                      WHERE username = :username AND guild_id = :guild_id")?
+            .execute(rusqlite::named_params! { ":username": username, ":guild_id": guild_id })
+            .inspect_err(|err| log::error!("[track_user] Error tracking user '{username}': {err}"))?;
+
+        Ok(())
+    }
+
+    fn untrack_user(&self, guild_id: u64, user: &models::User) -> Result<()> {
+        if !self.user_exists(user)? {
+            self.insert_user(user)?;
+        }
+
+        let connection = self.connect()?;
+        connection
+            .prepare("UPDATE UserPrefs SET tracked = 0
+                      WHERE username = :username AND guild_id = :guild_id")?
+            .execute(rusqlite::named_params! { ":username": user.username, ":guild_id": guild_id })?;
+
+        Ok(())
+    }
+
+    fn is_tracked(&self, guild_id: u64, user: &models::User) -> Result<bool> {
+        let connection = self.connect()?;
+        Ok(connection
+            .prepare("SELECT * FROM UserPrefs
+                      WHERE username = :username AND guild_id = :guild_id AND tracked = 1")?
+            .exists(rusqlite::named_params! { ":username": user.username, ":guild_id": guild_id })?)
+    }
+
+    fn query_user_preferences(&self, guild_id: u64, user: &models::User)
+        -> Result<Option<models::UserPreferences>>
+    {
+        let connection = self.connect()?;
+        Ok(connection
+            .prepare("SELECT * FROM UserPrefs WHERE username = :username AND guild_id = :guild_id")?
+            .query(rusqlite::named_params! { ":username": user.username, ":guild_id": guild_id })?
+            .next()?
+            .map(|row| row.try_into())
+            .transpose()?)
+    }
+
+    fn update_user_preferences(
+        &self,
+        guild_id: u64,
+        user: &models::User,
+        prefs: &models::UserPreferences
+    ) -> Result<()>
+    {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! {
+                ":guild_id":      guild_id,
+                ":username":      user.username,
+                ":tracked":       prefs.tracked,
+                ":announce":      prefs.announcement.is_some(),
+                ":announce_fail": prefs.announcement.as_ref().is_some_and(|a| a.announce_failures),
+                ":announce_link": prefs.announcement.as_ref().is_some_and(|a| a.has_submission_link),
+                ":announce_digest": prefs.announcement.as_ref().is_some_and(|a| a.digest)
+        };
+
+        connection
+            .prepare(
+                "UPDATE UserPrefs SET
+                    tracked = :tracked,
+                    announce = :announce,
+                    announce_fail = :announce_fail,
+                    announce_link = :announce_link,
+                    announce_digest = :announce_digest
+                 WHERE username = :username
+                   AND guild_id = :guild_id"
+            )?
+            .execute(query_params)
+            .inspect_err(|err| log::error!("[update_user_preferences] Could not update user \
+                                            preferences: {err}"))?;
+
+        Ok(())
+    }
+
+    fn insert_user_preferences(
+        &self,
+        guild_id: u64,
+        user: &models::User,
+        prefs: &models::UserPreferences
+    ) -> Result<bool>
+    {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! {
+                ":guild_id":      guild_id,
+                ":username":      user.username,
+                ":tracked":       prefs.tracked,
+                ":announce":      prefs.announcement.is_some(),
+                ":announce_fail": prefs.announcement.as_ref().is_some_and(|a| a.announce_failures),
+                ":announce_link": prefs.announcement.as_ref().is_some_and(|a| a.has_submission_link),
+                ":announce_digest": prefs.announcement.as_ref().is_some_and(|a| a.digest)
+        };
+
+        Ok(connection
+            .prepare(
+                "INSERT INTO UserPrefs ( guild_id,  username,  tracked,  announce,  announce_fail,
+                                         announce_link,  announce_digest)
+                 VALUES                (:guild_id, :username, :tracked, :announce, :announce_fail,
+                                        :announce_link, :announce_digest)"
+            )?
+            .execute(query_params)
+            .map_or_else(swallow_constraint_violation, |_| Ok(true))?)
+    }
+
+    fn is_active(&self, user: &models::User) -> Result<bool> {
+        let connection = self.connect()?;
+
+        // Get the current timestamp, approximately
+        let current_timestamp = current_timestamp();
+
+        let query_params = rusqlite::named_params! {
+                ":username":      user.username,
+                ":current_timestamp": current_timestamp,
+                ":DAY_IN_MILLIS": DAY_IN_MILLIS,
+        };
+
+        let is_tracked = connection
+            .prepare(
+                "SELECT 1
+                 FROM Users u
+                 JOIN UserPrefs   p ON p.username = u.username
+                 JOIN Submissions s ON s.username = u.username
+                 WHERE u.username = :username
+                   and p.tracked = 1
+                   and s.accepted = 1
+                   and :current_timestamp - s.timestamp < :DAY_IN_MILLIS",
+            )?
+            .exists(query_params)
+            .inspect_err(|err| log::error!("[is_active] Could not check if user was active: {err}"))?;
+
+        Ok(is_tracked)
+    }
+
+    fn streak_increment(&self, user: &models::User) -> Result<()> {
+        let connection = self.connect()?;
+        connection
+            .prepare(
+                "UPDATE Users
+                             SET streak = streak + 1
+                             WHERE username = ?",
+            )?
+            .execute(params![&user.username])?;
+
+        Ok(())
+    }
+
+    fn query_streak(&self, user: &models::User) -> Result<u64> {
+        log::trace!("[query_streak] Querying streak for {}...", user.username);
+        let connection = self.connect()?;
+        let mut stmt = connection.prepare("SELECT streak FROM Users WHERE username = ?")?;
+        Ok(stmt.query_row(params![&user.username], |row| row.get("streak"))?)
+    }
+
+    fn streak_break(&self, user: &models::User) -> Result<()> {
+        let connection = self.connect()?;
+        connection
+            .prepare("UPDATE Users SET streak = 0 WHERE username = ?")?
+            .execute(params![&user.username])?;
+
+        Ok(())
+    }
+
+    fn query_streak_freezes(&self, user: &models::User) -> Result<u64> {
+        log::trace!("[query_streak_freezes] Querying streak freezes for {}...", user.username);
+        let connection = self.connect()?;
+        let mut stmt = connection.prepare("SELECT freezes FROM Users WHERE username = ?")?;
+        Ok(stmt.query_row(params![&user.username], |row| row.get("freezes"))?)
+    }
+
+    fn update_streak_freezes(&self, user: &models::User, freezes: u64) -> Result<()> {
+        let connection = self.connect()?;
+        connection
+            .prepare("UPDATE Users SET freezes = ? WHERE username = ?")?
+            .execute(params![freezes, &user.username])?;
+
+        Ok(())
+    }
+
+    /////*============== PROBLEM QUERIES ==============*/
+    fn insert_problem(&self, problem: &models::Problem) -> Result<bool> {
+        let connection = self.connect()?;
+
+        log::trace!(
+            "[insert_problem] Inserting problem {} into Problems...",
+            problem.title
+        );
+
+        let query_params = rusqlite::named_params! {
+                ":problem_name": problem.title,
+                ":problem_link": problem_link(problem),
+                ":difficulty":   problem.difficulty
+        };
+
+        Ok(connection
+            .prepare(
+                "INSERT INTO Problems ( problem_name,  problem_link,  difficulty)
+             VALUES                         (:problem_name, :problem_link, :difficulty)",
+            )?
+            .execute(query_params)
+            .map_or_else(swallow_constraint_violation, |_| Ok(true))?)
+    }
+
+    fn query_problem(&self, title: &str) -> Result<Option<models::Problem>> {
+        let connection = self.connect()?;
+        Ok(connection
+            .prepare("SELECT * FROM Problems WHERE problem_name = :problem_name")?
+            .query(rusqlite::named_params! { ":problem_name": title })?
+            .next()?
+            .map(|row| row.try_into())
+            .transpose()?)
+    }
+
+    fn update_problem_difficulty(&self, title: &str, difficulty: &str) -> Result<()> {
+        self.connect()?.execute(
+            "UPDATE Problems SET difficulty = :difficulty WHERE problem_name = :problem_name",
+            rusqlite::named_params! { ":difficulty": difficulty, ":problem_name": title },
+        )?;
 
-/////*============== PROBLEM QUERIES ==============*/
-/// Inserts the problem into Problems, or does nothing if it already is there.
-/// Returns `true` if it was newly added, false otherwise.
-pub fn insert_problem(problem: &models::Problem) -> DBResult<bool> {
-    let connection = connect()?;
-
-    log::trace!(
-        "[insert_problem] Inserting problem {} into Problems...",
-        problem.title
-    );
-
-    let query_params = rusqlite::named_params! {
-            ":problem_name": problem.title,
-            ":problem_link": format!("https://leetcode.com/problems/{}", problem.url),
-            ":difficulty":   problem.difficulty
-    };
-
-    connection
-        .prepare(
-            "INSERT INTO Problems ( problem_name,  problem_link,  difficulty)
-         VALUES                         (:problem_name, :problem_link, :difficulty)",
-        )?
-        .execute(query_params)
-        .map_or_else(swallow_constraint_violation, |_| Ok(true))
+        Ok(())
+    }
+
+    fn query_problem_titles(&self, search: &str, limit: usize) -> Result<Vec<String>> {
+        let connection = self.connect()?;
+        let titles = connection
+            .prepare(
+                "SELECT problem_name FROM Problems
+                 WHERE instr(lower(problem_name), lower(:search)) > 0
+                 ORDER BY problem_name
+                 LIMIT :limit",
+            )?
+            .query_map(rusqlite::named_params! { ":search": search, ":limit": limit },
+                       |row| row.get("problem_name"))?
+            .collect::<DBResult<Vec<String>>>()?;
+
+        Ok(titles)
+    }
+
+    /////*============== GUILD QUERIES ==============*/
+    fn query_guilds(&self) -> Result<Vec<u64>> {
+        let connection = self.connect()?;
+        let guilds = connection
+            .prepare("SELECT guild_id FROM GuildSettings")?
+            .query_map([], |row| row.get("guild_id"))?
+            .collect::<DBResult<Vec<u64>>>()?;
+
+        Ok(guilds)
+    }
+
+    fn query_guild_settings(&self, guild_id: u64) -> Result<models::GuildSettings> {
+        let connection = self.connect()?;
+        Ok(connection
+            .prepare("SELECT * FROM GuildSettings WHERE guild_id = :guild_id")?
+            .query(rusqlite::named_params! { ":guild_id": guild_id })?
+            .next()?
+            .map(|row| row.try_into())
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    fn update_guild_settings(&self, guild_id: u64, settings: &models::GuildSettings)
+        -> Result<()>
+    {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! {
+                ":guild_id":              guild_id,
+                ":announcements_channel": settings.announcements_channel,
+                ":admin_role":            settings.admin_role,
+                ":prefix":                settings.prefix,
+                ":accepted_channel":      settings.accepted_channel,
+                ":failures_channel":      settings.failures_channel,
+                ":streaks_channel":       settings.streaks_channel,
+                ":updates_channel":       settings.updates_channel,
+                ":recaps_channel":        settings.recaps_channel,
+                ":digest_window":         settings.digest_window,
+                ":daily_recap":           settings.daily_recap,
+                ":weekly_recap":          settings.weekly_recap,
+                ":recap_hour":            settings.recap_hour,
+                ":recap_day":             settings.recap_day,
+                ":easy_points":           settings.easy_points,
+                ":medium_points":         settings.medium_points,
+                ":hard_points":           settings.hard_points,
+                ":leaderboard_channel":   settings.leaderboard_channel,
+                ":leaderboard_message":   settings.leaderboard_message,
+                ":leaderboard_period":    settings.leaderboard_period,
+                ":seasons_channel":       settings.seasons_channel,
+                ":season_length":         settings.season_length,
+                ":season_number":         settings.season_number,
+                ":season_start":          settings.season_start,
+                ":challenges_channel":    settings.challenges_channel,
+                ":goals_channel":         settings.goals_channel,
+                ":goal_count":            settings.goal_count,
+                ":goal_difficulty":       settings.goal_difficulty,
+                ":goal_milestone":        settings.goal_milestone,
+                ":goal_milestone_at":     settings.goal_milestone_at,
+        };
+
+        connection
+            .prepare(
+                "INSERT OR REPLACE INTO GuildSettings
+                        ( guild_id,  announcements_channel,  admin_role,  prefix,
+                          accepted_channel,  failures_channel,  streaks_channel,  updates_channel,
+                          recaps_channel,  digest_window,  daily_recap,  weekly_recap,  recap_hour,
+                          recap_day,  easy_points,  medium_points,  hard_points,  leaderboard_channel,
+                          leaderboard_message,  leaderboard_period,  seasons_channel,  season_length,
+                          season_number,  season_start,  challenges_channel,  goals_channel,
+                          goal_count,  goal_difficulty,  goal_milestone,  goal_milestone_at)
+                 VALUES (:guild_id, :announcements_channel, :admin_role, :prefix,
+                         :accepted_channel, :failures_channel, :streaks_channel, :updates_channel,
+                         :recaps_channel, :digest_window, :daily_recap, :weekly_recap, :recap_hour,
+                         :recap_day, :easy_points, :medium_points, :hard_points, :leaderboard_channel,
+                         :leaderboard_message, :leaderboard_period, :seasons_channel, :season_length,
+                         :season_number, :season_start, :challenges_channel, :goals_channel,
+                         :goal_count, :goal_difficulty, :goal_milestone, :goal_milestone_at)"
+            )?
+            .execute(query_params)
+            .inspect_err(|err| log::error!("[update_guild_settings] Could not update settings \
+                                            for guild {guild_id}: {err}"))?;
+
+        Ok(())
+    }
+
+    fn claim_legacy_preferences(&self, guild_id: u64) -> Result<usize> {
+        if !self.table_exists("UserPrefsLegacy")? {
+            return Ok(0);
+        }
+
+        let connection = self.connect()?;
+        let claimed = connection
+            .prepare(
+                "INSERT OR IGNORE INTO UserPrefs
+                    (guild_id, username, tracked, announce, announce_fail, announce_link)
+                 SELECT :guild_id, username, tracked, announce, announce_fail, announce_link
+                 FROM UserPrefsLegacy"
+            )?
+            .execute(rusqlite::named_params! { ":guild_id": guild_id })?;
+
+        connection.execute("DROP TABLE UserPrefsLegacy", [])?;
+        log::info!("[claim_legacy_preferences] Moved {claimed} users' preferences into guild \
+                    {guild_id}.");
+
+        Ok(claimed)
+    }
+
+    /////*============== SEASON QUERIES ==============*/
+    fn archive_season(&self, guild_id: u64, season: &models::Season) -> Result<()> {
+        let mut connection = self.connect()?;
+        let transaction = connection.transaction()?;
+
+        transaction
+            .prepare(
+                "INSERT OR REPLACE INTO Seasons ( guild_id,  season,  started_at,  ended_at)
+                 VALUES                         (:guild_id, :season, :started_at, :ended_at)"
+            )?
+            .execute(rusqlite::named_params! {
+                ":guild_id":   guild_id,
+                ":season":     season.number,
+                ":started_at": season.started_at,
+                ":ended_at":   season.ended_at,
+            })?;
+
+        for (rank, entry) in season.standings.iter().enumerate() {
+            transaction
+                .prepare(
+                    "INSERT OR REPLACE INTO SeasonStandings
+                            ( guild_id,  season,  rank,  username,  points,  solved,  reached_at)
+                     VALUES (:guild_id, :season, :rank, :username, :points, :solved, :reached_at)"
+                )?
+                .execute(rusqlite::named_params! {
+                    ":guild_id":   guild_id,
+                    ":season":     season.number,
+                    ":rank":       rank + 1,
+                    ":username":   entry.username,
+                    ":points":     entry.points,
+                    ":solved":     entry.solved,
+                    ":reached_at": entry.reached_at,
+                })?;
+        }
+
+        transaction
+            .commit()
+            .inspect_err(|err| log::error!("[archive_season] Could not archive season {} of \
+                                            guild {guild_id}: {err}", season.number))?;
+
+        Ok(())
+    }
+
+    fn query_season(&self, guild_id: u64, number: u64) -> Result<Option<models::Season>> {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! { ":guild_id": guild_id, ":season": number };
+
+        let Some((started_at, ended_at)) = connection
+            .prepare("SELECT * FROM Seasons WHERE guild_id = :guild_id AND season = :season")?
+            .query(query_params)?
+            .next()?
+            .map(|row| Ok::<_, rusqlite::Error>((row.get("started_at")?, row.get("ended_at")?)))
+            .transpose()?
+        else {
+            return Ok(None);
+        };
+
+        let standings = connection
+            .prepare("SELECT * FROM SeasonStandings
+                      WHERE guild_id = :guild_id AND season = :season
+                      ORDER BY rank")?
+            .query_map(query_params, |row| models::LeaderboardEntry::try_from(row))?
+            .collect::<DBResult<Vec<_>>>()?;
+
+        Ok(Some(models::Season { number, started_at, ended_at, standings, is_over: true }))
+    }
+
+    /////*============== CHALLENGE QUERIES ==============*/
+    fn insert_challenge(&self, challenge: &models::Challenge) -> Result<u64> {
+        let connection = self.connect()?;
+        connection
+            .prepare(
+                "INSERT INTO Challenges
+                        ( guild_id,  challenger,  challenged,  problem_name,  issued_at,  deadline,
+                          outcome,  resolved_at)
+                 VALUES (:guild_id, :challenger, :challenged, :problem_name, :issued_at, :deadline,
+                         :outcome, :resolved_at)"
+            )?
+            .execute(rusqlite::named_params! {
+                ":guild_id":     challenge.guild_id,
+                ":challenger":   challenge.challenger,
+                ":challenged":   challenge.challenged,
+                ":problem_name": challenge.problem,
+                ":issued_at":    challenge.issued_at,
+                ":deadline":     challenge.deadline,
+                ":outcome":      challenge.outcome.name(),
+                ":resolved_at":  challenge.resolved_at,
+            })
+            .inspect_err(|err| log::error!("[insert_challenge] Could not insert challenge of {} \
+                                            by {}: {err}", challenge.challenged,
+                                            challenge.challenger))?;
+
+        Ok(connection.last_insert_rowid() as u64)
+    }
+
+    fn query_open_challenges(&self) -> Result<Vec<models::Challenge>> {
+        let query_params =
+            rusqlite::named_params! { ":outcome": models::ChallengeOutcome::Open.name() };
+
+        Ok(self
+            .connect()?
+            .prepare("SELECT * FROM Challenges WHERE outcome = :outcome ORDER BY deadline")?
+            .query_map(query_params, |row| models::Challenge::try_from(row))?
+            .collect::<DBResult<Vec<_>>>()?)
+    }
+
+    fn resolve_challenge(&self, id: u64, outcome: models::ChallengeOutcome, resolved_at: usize)
+        -> Result<()>
+    {
+        self.connect()?.execute(
+            "UPDATE Challenges SET outcome = :outcome, resolved_at = :resolved_at
+             WHERE challenge_id = :challenge_id",
+            rusqlite::named_params! {
+                ":outcome":      outcome.name(),
+                ":resolved_at":  resolved_at,
+                ":challenge_id": id,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    fn query_challenge_record(&self, guild_id: u64, username: &str)
+        -> Result<models::ChallengeRecord>
+    {
+        // The challenged user wins the challenges they solve, and the challenger the ones that
+        // expire.
+        let (wins, losses) = self
+            .connect()?
+            .prepare(
+                "SELECT
+                    COALESCE(SUM((challenged = :username AND outcome = 'won')
+                              OR (challenger = :username AND outcome = 'expired')), 0),
+                    COALESCE(SUM((challenged = :username AND outcome = 'expired')
+                              OR (challenger = :username AND outcome = 'won')), 0)
+                 FROM Challenges
+                 WHERE guild_id = :guild_id"
+            )?
+            .query_row(rusqlite::named_params! { ":guild_id": guild_id, ":username": username },
+                       |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        Ok(models::ChallengeRecord { wins, losses })
+    }
+
+    /////*============== LIST QUERIES ==============*/
+    fn replace_problem_list(&self, guild_id: u64, list: &models::ProblemList) -> Result<()> {
+        let mut connection = self.connect()?;
+        let transaction = connection.transaction()?;
+
+        transaction
+            .prepare("DELETE FROM ProblemLists
+                      WHERE guild_id = :guild_id AND list_name = :list_name")?
+            .execute(rusqlite::named_params! { ":guild_id": guild_id, ":list_name": list.name })?;
+
+        for (position, problem) in list.problems.iter().enumerate() {
+            transaction
+                .prepare(
+                    "INSERT OR IGNORE INTO ProblemLists
+                            ( guild_id,  list_name,  position,  problem_name)
+                     VALUES (:guild_id, :list_name, :position, :problem_name)"
+                )?
+                .execute(rusqlite::named_params! {
+                    ":guild_id":     guild_id,
+                    ":list_name":    list.name,
+                    ":position":     position,
+                    ":problem_name": problem,
+                })?;
+        }
+
+        transaction
+            .commit()
+            .inspect_err(|err| log::error!("[replace_problem_list] Could not store list {} of \
+                                            guild {guild_id}: {err}", list.name))?;
+
+        Ok(())
+    }
+
+    fn query_problem_list(&self, guild_id: u64, name: &str) -> Result<Option<models::ProblemList>> {
+        let problems = self
+            .connect()?
+            .prepare("SELECT problem_name FROM ProblemLists
+                      WHERE guild_id = :guild_id AND list_name = :list_name
+                      ORDER BY position")?
+            .query_map(rusqlite::named_params! { ":guild_id": guild_id, ":list_name": name },
+                       |row| row.get(0))?
+            .collect::<DBResult<Vec<String>>>()?;
+
+        Ok((!problems.is_empty()).then(|| models::ProblemList { name: name.to_owned(), problems }))
+    }
+
+    fn query_problem_list_names(&self, guild_id: u64) -> Result<Vec<String>> {
+        Ok(self
+            .connect()?
+            .prepare("SELECT DISTINCT list_name FROM ProblemLists
+                      WHERE guild_id = :guild_id
+                      ORDER BY list_name")?
+            .query_map(rusqlite::named_params! { ":guild_id": guild_id }, |row| row.get(0))?
+            .collect::<DBResult<Vec<String>>>()?)
+    }
+
+    fn update_list_follow(&self, guild_id: u64, username: &str, name: &str, following: bool)
+        -> Result<()>
+    {
+        let query = match following {
+            true => "INSERT OR IGNORE INTO ListFollows ( guild_id,  username,  list_name)
+                     VALUES                            (:guild_id, :username, :list_name)",
+            false => "DELETE FROM ListFollows
+                      WHERE guild_id = :guild_id AND username = :username
+                        AND list_name = :list_name",
+        };
+
+        self.connect()?.execute(query, rusqlite::named_params! {
+            ":guild_id":  guild_id,
+            ":username":  username,
+            ":list_name": name,
+        })?;
+
+        Ok(())
+    }
+
+    fn query_followed_lists(&self, guild_id: u64, username: &str) -> Result<Vec<String>> {
+        Ok(self
+            .connect()?
+            .prepare("SELECT list_name FROM ListFollows
+                      WHERE guild_id = :guild_id AND username = :username
+                      ORDER BY list_name")?
+            .query_map(rusqlite::named_params! { ":guild_id": guild_id, ":username": username },
+                       |row| row.get(0))?
+            .collect::<DBResult<Vec<String>>>()?)
+    }
+
+    /////*============== REMINDER QUERIES ==============*/
+    fn update_streak_reminder(&self, reminder: &models::StreakReminder) -> Result<()> {
+        self.connect()?
+            .prepare(
+                "INSERT OR REPLACE INTO StreakReminders ( discord_id,  hours_before,  channel_id)
+                 VALUES                                 (:discord_id, :hours_before, :channel_id)"
+            )?
+            .execute(rusqlite::named_params! {
+                ":discord_id":   reminder.discord_id,
+                ":hours_before": reminder.hours_before,
+                ":channel_id":   reminder.channel_id,
+            })
+            .inspect_err(|err| log::error!("[update_streak_reminder] Could not update reminder \
+                                            of {}: {err}", reminder.discord_id))?;
+
+        Ok(())
+    }
+
+    fn delete_streak_reminder(&self, discord_id: u64) -> Result<()> {
+        self.connect()?.execute(
+            "DELETE FROM StreakReminders WHERE discord_id = :discord_id",
+            rusqlite::named_params! { ":discord_id": discord_id },
+        )?;
+
+        Ok(())
+    }
+
+    fn query_streak_reminder(&self, discord_id: u64) -> Result<Option<models::StreakReminder>> {
+        Ok(self
+            .connect()?
+            .prepare("SELECT * FROM StreakReminders WHERE discord_id = :discord_id")?
+            .query(rusqlite::named_params! { ":discord_id": discord_id })?
+            .next()?
+            .map(|row| row.try_into())
+            .transpose()?)
+    }
+
+    fn query_streak_reminders(&self) -> Result<Vec<models::StreakReminder>> {
+        Ok(self
+            .connect()?
+            .prepare("SELECT * FROM StreakReminders")?
+            .query_map([], |row| models::StreakReminder::try_from(row))?
+            .collect::<DBResult<Vec<_>>>()?)
+    }
+
+    /////*============== KUDOS QUERIES ==============*/
+    fn insert_submission_announcement(&self, announcement: &models::SubmissionAnnouncement)
+        -> Result<()>
+    {
+        self.connect()?
+            .prepare(
+                "INSERT OR IGNORE INTO SubmissionAnnouncements ( message_id,  guild_id,  username)
+                 VALUES                                        (:message_id, :guild_id, :username)"
+            )?
+            .execute(rusqlite::named_params! {
+                ":message_id": announcement.message_id,
+                ":guild_id":   announcement.guild_id,
+                ":username":   announcement.username,
+            })
+            .inspect_err(|err| log::error!("[insert_submission_announcement] Could not record \
+                                            announcement {}: {err}", announcement.message_id))?;
+
+        Ok(())
+    }
+
+    fn query_submission_announcement(&self, message_id: u64)
+        -> Result<Option<models::SubmissionAnnouncement>>
+    {
+        Ok(self
+            .connect()?
+            .prepare("SELECT * FROM SubmissionAnnouncements WHERE message_id = :message_id")?
+            .query(rusqlite::named_params! { ":message_id": message_id })?
+            .next()?
+            .map(|row| row.try_into())
+            .transpose()?)
+    }
+
+    fn insert_kudos(&self, kudos: &models::Kudos) -> Result<()> {
+        self.connect()?
+            .prepare(
+                "INSERT OR IGNORE INTO Kudos ( message_id,  giver_id,  emoji,  given_at)
+                 VALUES                      (:message_id, :giver_id, :emoji, :given_at)"
+            )?
+            .execute(rusqlite::named_params! {
+                ":message_id": kudos.message_id,
+                ":giver_id":   kudos.giver_id,
+                ":emoji":      kudos.emoji,
+                ":given_at":   kudos.given_at,
+            })
+            .inspect_err(|err| log::error!("[insert_kudos] Could not record kudos from {} on \
+                                            {}: {err}", kudos.giver_id, kudos.message_id))?;
+
+        Ok(())
+    }
+
+    fn delete_kudos(&self, message_id: u64, giver_id: u64, emoji: &str) -> Result<()> {
+        self.connect()?.execute(
+            "DELETE FROM Kudos
+             WHERE message_id = :message_id AND giver_id = :giver_id AND emoji = :emoji",
+            rusqlite::named_params! {
+                ":message_id": message_id,
+                ":giver_id":   giver_id,
+                ":emoji":      emoji,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    fn query_kudos_totals(&self, guild_id: u64, since: usize, until: usize)
+        -> Result<Vec<(String, u64)>>
+    {
+        // However many emojis a member reacts with, they give one kudos per announcement.
+        Ok(self
+            .connect()?
+            .prepare(
+                "SELECT a.username, COUNT(DISTINCT k.message_id || ':' || k.giver_id) AS kudos
+                 FROM Kudos k
+                 JOIN SubmissionAnnouncements a ON k.message_id = a.message_id
+                 WHERE a.guild_id = :guild_id
+                   AND k.given_at >= :since
+                   AND k.given_at < :until
+                 GROUP BY a.username
+                 ORDER BY kudos DESC, a.username"
+            )?
+            .query_map(rusqlite::named_params! {
+                ":guild_id": guild_id,
+                ":since":    since,
+                ":until":    until,
+            }, |row| Ok((row.get("username")?, row.get("kudos")?)))?
+            .collect::<DBResult<Vec<_>>>()?)
+    }
+
+    /////*============== LINK QUERIES ==============*/
+    fn link_account(&self, discord_id: u64, username: &str) -> Result<()> {
+        log::trace!("[link_account] Linking Discord account {discord_id} to {username}...");
+        let connection = self.connect()?;
+        connection
+            .prepare("INSERT INTO Links (discord_id, username)
+                      VALUES (:discord_id, :username)
+                      ON CONFLICT (discord_id) DO UPDATE SET
+                        verified = verified AND username = excluded.username,
+                        username = excluded.username")?
+            .execute(rusqlite::named_params! { ":discord_id": discord_id, ":username": username })?;
+
+        Ok(())
+    }
+
+    fn query_linked_username(&self, discord_id: u64) -> Result<Option<String>> {
+        let connection = self.connect()?;
+        Ok(connection
+            .prepare("SELECT username FROM Links WHERE discord_id = :discord_id")?
+            .query(rusqlite::named_params! { ":discord_id": discord_id })?
+            .next()?
+            .map(|row| row.get("username"))
+            .transpose()?)
+    }
+
+    fn is_verified_owner(&self, discord_id: u64, username: &str) -> Result<bool> {
+        let connection = self.connect()?;
+        Ok(connection
+            .prepare("SELECT 1 FROM Links
+                      WHERE discord_id = :discord_id
+                        AND username = :username COLLATE NOCASE
+                        AND verified = 1")?
+            .exists(rusqlite::named_params! { ":discord_id": discord_id, ":username": username })?)
+    }
+
+    /////*============== VERIFICATION QUERIES ==============*/
+    fn insert_verification(&self, discord_id: u64, username: &str, token: &str) -> Result<()> {
+        log::trace!("[insert_verification] Verifying {username} for Discord account {discord_id}...");
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! {
+                ":discord_id": discord_id,
+                ":username":   username,
+                ":token":      token,
+                ":issued_at":  current_timestamp(),
+        };
+
+        connection
+            .prepare("INSERT OR REPLACE INTO Verifications ( discord_id,  username,  token,  issued_at)
+                      VALUES                               (:discord_id, :username, :token, :issued_at)")?
+            .execute(query_params)?;
+
+        Ok(())
+    }
+
+    fn query_verification(&self, discord_id: u64) -> Result<Option<models::Verification>> {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! {
+                ":discord_id": discord_id,
+                ":current_timestamp": current_timestamp(),
+                ":verification_threshold": models::VERIFICATION_THRESHOLD,
+        };
+
+        Ok(connection
+            .prepare("SELECT * FROM Verifications
+                      WHERE discord_id = :discord_id
+                        AND :current_timestamp - issued_at < :verification_threshold")?
+            .query(query_params)?
+            .next()?
+            .map(|row| row.try_into())
+            .transpose()?)
+    }
+
+    fn complete_verification(&self, discord_id: u64) -> Result<()> {
+        let connection = self.connect()?;
+        let query_params = rusqlite::named_params! { ":discord_id": discord_id };
+
+        connection
+            .prepare("INSERT OR REPLACE INTO Links (discord_id, username, verified)
+                      SELECT discord_id, username, 1 FROM Verifications
+                      WHERE discord_id = :discord_id")?
+            .execute(query_params)?;
+        connection
+            .prepare("DELETE FROM Verifications WHERE discord_id = :discord_id")?
+            .execute(query_params)?;
+
+        Ok(())
+    }
 }
 
 /////*============== INTERNAL API ==============*/
-/// [internal] Checks if the user is in the database.
-fn user_exists(user: &models::User) -> DBResult<bool> {
-    let connection = connect()?;
-    connection
-        .prepare("SELECT * FROM Users WHERE username = :username")?
-        .exists(rusqlite::named_params!{ ":username": user.username })
+const DAY_IN_MILLIS: usize = 86_400_000;
+
+/// [internal] Gets the current timestamp in milliseconds, approximately.
+fn current_timestamp() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards????")
+        .as_millis() as usize
+}
+
+/// [internal] The link stored for a problem; `problem.url` is its slug when it comes from LeetCode.
+fn problem_link(problem: &models::Problem) -> String {
+    format!("https://leetcode.com/problems/{}", problem.url)
 }
 
 fn swallow_constraint_violation(err: rusqlite::Error) -> DBResult<bool> {
@@ -612,36 +1954,3 @@ fn swallow_constraint_violation(err: rusqlite::Error) -> DBResult<bool> {
         _ => Err(err)
     }
 }
-
-pub fn insert_fake_submission(
-    user: &models::User,
-    problem_name: String,
-    accepted: bool,
-) -> DBResult<()> {
-    let problem = models::Problem {
-        title: problem_name,
-        url: String::from("no_url"),
-        difficulty: String::from("no difficulty"),
-    };
-
-    insert_problem(&problem)?;
-
-    let submission = models::Submission {
-        username: user.username.to_owned(),
-        problem,
-        language: String::from("no_language"),
-        timestamp: {
-            // Get the current timestamp, approximately
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards????")
-                .as_millis() as usize
-        },
-        accepted,
-        url: String::from("no_url"),
-    };
-
-    insert_submission(&submission)?;
-
-    Ok(())
-}
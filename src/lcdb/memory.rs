@@ -0,0 +1,957 @@
+use anyhow::{Result, anyhow};
+use itertools::Itertools;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+use super::{DAY_IN_MILLIS, Store, current_timestamp, problem_link};
+use crate::models;
+
+/// A `Store` that keeps everything in memory. Nothing survives the process.
+#[derive(Default)]
+pub struct MemoryStore {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    users: HashMap<String, models::User>,
+    /// Streak freezes saved up, by username. Users without any aren't in there.
+    streak_freezes: HashMap<String, u64>,
+    /// Keyed by (guild_id, username)
+    prefs: HashMap<(u64, String), models::UserPreferences>,
+    guild_settings: HashMap<u64, models::GuildSettings>,
+    /// Discord ID -> (LeetCode username, verified)
+    links: HashMap<u64, (String, bool)>,
+    verifications: HashMap<u64, models::Verification>,
+    /// Keyed by (guild_id, season number)
+    seasons: HashMap<(u64, u64), models::Season>,
+    /// In the order they were issued: a challenge's ID is its position, counting from 1.
+    challenges: Vec<models::Challenge>,
+    /// Keyed by (guild_id, list name)
+    problem_lists: HashMap<(u64, String), models::ProblemList>,
+    /// (guild_id, username, list name)
+    list_follows: HashSet<(u64, String, String)>,
+    /// Keyed by Discord ID
+    streak_reminders: HashMap<u64, models::StreakReminder>,
+    /// Keyed by message ID
+    submission_announcements: HashMap<u64, models::SubmissionAnnouncement>,
+    /// Keyed by (message ID, giver's Discord ID, emoji)
+    kudos: HashMap<(u64, u64, String), models::Kudos>,
+    problems: HashMap<String, models::Problem>,
+    submissions: Vec<models::Submission>,
+
+    /// (username, problem_name, timestamp, accepted)
+    recent_cache: HashSet<(String, String, usize, bool)>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, MemoryState>> {
+        self.state.lock().map_err(|_| anyhow!("MemoryStore lock was poisoned."))
+    }
+}
+
+impl MemoryState {
+    /// Mirrors the Submissions/Problems join: the problem comes from the Problems table.
+    fn joined(&self, submission: &models::Submission) -> Option<models::Submission> {
+        let problem = self.problems.get(&submission.problem.title)?.clone();
+        Some(models::Submission { problem, ..submission.clone() })
+    }
+
+    fn recent_submissions<'a>(&'a self, username: &'a str)
+        -> impl Iterator<Item = &'a models::Submission>
+    {
+        let current_timestamp = current_timestamp();
+        self.submissions
+            .iter()
+            .filter(move |s| s.username == username)
+            .filter(move |s| current_timestamp.saturating_sub(s.timestamp) < models::RECENT_THRESHOLD)
+    }
+
+    fn sorted_newest_first(mut submissions: Vec<models::Submission>) -> Vec<models::Submission> {
+        submissions.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+        submissions
+    }
+
+    fn ensure_user(&mut self, user: &models::User) {
+        self.users
+            .entry(user.username.clone())
+            .or_insert_with(|| user.clone());
+    }
+
+    fn is_tracked_anywhere(&self, username: &str) -> bool {
+        self.prefs
+            .iter()
+            .any(|((_, name), prefs)| name == username && prefs.tracked)
+    }
+}
+
+impl Store for MemoryStore {
+    /////*============== SUBMISSION QUERIES ==============*/
+    fn query_submissions_recent_all(&self, user: &models::User) -> Result<Vec<models::Submission>> {
+        let state = self.state()?;
+        let submissions = state
+            .recent_submissions(&user.username)
+            .filter_map(|s| state.joined(s))
+            .collect();
+
+        Ok(MemoryState::sorted_newest_first(submissions))
+    }
+
+    fn query_submissions_between(&self, user: &models::User, since: usize, until: usize)
+        -> Result<Vec<models::Submission>>
+    {
+        let state = self.state()?;
+        let submissions = state
+            .submissions
+            .iter()
+            .filter(|s| s.username == user.username && (since..until).contains(&s.timestamp))
+            .filter_map(|s| state.joined(s))
+            .collect();
+
+        Ok(MemoryState::sorted_newest_first(submissions))
+    }
+
+    fn query_solved_before(&self, user: &models::User, before: usize) -> Result<Vec<String>> {
+        let state = self.state()?;
+        let solved = state
+            .submissions
+            .iter()
+            .filter(|s| s.username == user.username && s.accepted && s.timestamp < before)
+            .map(|s| s.problem.title.clone())
+            .unique()
+            .collect();
+
+        Ok(solved)
+    }
+
+    fn insert_submission(&self, submission: &models::Submission) -> Result<bool> {
+        let mut state = self.state()?;
+        let exists = state.submissions.iter().any(|s| {
+            s.problem.title == submission.problem.title
+                && s.username == submission.username
+                && s.timestamp == submission.timestamp
+        });
+
+        if !exists {
+            state.submissions.push(submission.clone());
+        }
+
+        Ok(!exists)
+    }
+
+    fn insert_fake_submission(
+        &self,
+        user: &models::User,
+        problem_name: String,
+        accepted: bool,
+        timestamp: usize,
+    ) -> Result<()> {
+        let problem = models::Problem {
+            title: problem_name,
+            url: String::from("no_url"),
+            difficulty: String::from("no difficulty"),
+        };
+
+        self.insert_problem(&problem)?;
+        self.insert_submission(&models::Submission {
+            username: user.username.to_owned(),
+            problem,
+            language: String::from("no_language"),
+            timestamp,
+            accepted,
+            url: String::from("no_url"),
+        })?;
+
+        Ok(())
+    }
+
+    /////*============== RECENT CACHE QUERIES ==============*/
+    fn query_uncached_submissions(&self, user: &models::User) -> Result<Vec<models::Submission>> {
+        let state = self.state()?;
+        let submissions = state
+            .recent_submissions(&user.username)
+            .filter(|s| {
+                !state
+                    .recent_cache
+                    .iter()
+                    .any(|(username, _, timestamp, _)| {
+                        *username == s.username && *timestamp == s.timestamp
+                    })
+            })
+            .filter_map(|s| state.joined(s))
+            .collect();
+
+        Ok(MemoryState::sorted_newest_first(submissions))
+    }
+
+    fn insert_cache_submission(&self, submission: &models::Submission) -> Result<bool> {
+        Ok(self.state()?.recent_cache.insert((
+            submission.username.clone(),
+            submission.problem.title.clone(),
+            submission.timestamp,
+            submission.accepted,
+        )))
+    }
+
+    fn clean_cache(&self) -> Result<()> {
+        let current_timestamp = current_timestamp();
+        self.state()?.recent_cache.retain(|(_, _, timestamp, _)| {
+            current_timestamp.saturating_sub(*timestamp) <= models::RECENT_THRESHOLD
+        });
+
+        Ok(())
+    }
+
+    /////*============== USER QUERIES ==============*/
+    fn query_user(&self, username: &str) -> Result<Option<models::User>> {
+        Ok(self.state()?.users.get(username).cloned())
+    }
+
+    fn query_tracked_users(&self) -> Result<Vec<models::User>> {
+        let state = self.state()?;
+        Ok(state
+            .users
+            .values()
+            .filter(|u| state.is_tracked_anywhere(&u.username))
+            .cloned()
+            .collect())
+    }
+
+    fn query_guild_tracked_users(&self, guild_id: u64) -> Result<Vec<models::User>> {
+        let state = self.state()?;
+        Ok(state
+            .users
+            .values()
+            .filter(|u| {
+                state
+                    .prefs
+                    .get(&(guild_id, u.username.clone()))
+                    .is_some_and(|p| p.tracked)
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn query_tracking_guilds(&self, user: &models::User)
+        -> Result<Vec<(u64, models::UserPreferences)>>
+    {
+        Ok(self
+            .state()?
+            .prefs
+            .iter()
+            .filter(|((_, username), prefs)| *username == user.username && prefs.tracked)
+            .map(|((guild_id, _), prefs)| (*guild_id, *prefs))
+            .collect())
+    }
+
+    fn insert_user(&self, user: &models::User) -> Result<()> {
+        let mut state = self.state()?;
+        if state.users.contains_key(&user.username) {
+            return Err(anyhow!("User {} is already in the database.", user.username));
+        }
+
+        state.users.insert(user.username.clone(), user.clone());
+
+        Ok(())
+    }
+
+    fn track_user(&self, guild_id: u64, user: &models::User) -> Result<()> {
+        let mut state = self.state()?;
+        state.ensure_user(user);
+        state
+            .prefs
+            .entry((guild_id, user.username.clone()))
+            .or_insert(models::DEFAULT_USER_PREFERENCES)
+            .tracked = true;
+
+        Ok(())
+    }
+
+    fn untrack_user(&self, guild_id: u64, user: &models::User) -> Result<()> {
+        let mut state = self.state()?;
+        state.ensure_user(user);
+        if let Some(prefs) = state.prefs.get_mut(&(guild_id, user.username.clone())) {
+            prefs.tracked = false;
+        }
+
+        Ok(())
+    }
+
+    fn is_tracked(&self, guild_id: u64, user: &models::User) -> Result<bool> {
+        Ok(self
+            .state()?
+            .prefs
+            .get(&(guild_id, user.username.clone()))
+            .is_some_and(|p| p.tracked))
+    }
+
+    fn query_user_preferences(&self, guild_id: u64, user: &models::User)
+        -> Result<Option<models::UserPreferences>>
+    {
+        Ok(self.state()?.prefs.get(&(guild_id, user.username.clone())).copied())
+    }
+
+    fn update_user_preferences(
+        &self,
+        guild_id: u64,
+        user: &models::User,
+        prefs: &models::UserPreferences,
+    ) -> Result<()> {
+        if let Some(existing) = self.state()?.prefs.get_mut(&(guild_id, user.username.clone())) {
+            *existing = *prefs;
+        }
+
+        Ok(())
+    }
+
+    fn insert_user_preferences(
+        &self,
+        guild_id: u64,
+        user: &models::User,
+        prefs: &models::UserPreferences,
+    ) -> Result<bool> {
+        let mut state = self.state()?;
+        let key = (guild_id, user.username.clone());
+        if state.prefs.contains_key(&key) {
+            return Ok(false);
+        }
+
+        state.prefs.insert(key, *prefs);
+        Ok(true)
+    }
+
+    fn is_active(&self, user: &models::User) -> Result<bool> {
+        let state = self.state()?;
+        let current_timestamp = current_timestamp();
+
+        Ok(state.is_tracked_anywhere(&user.username)
+            && state.submissions.iter().any(|s| {
+                s.username == user.username
+                    && s.accepted
+                    && current_timestamp.saturating_sub(s.timestamp) < DAY_IN_MILLIS
+            }))
+    }
+
+    fn streak_increment(&self, user: &models::User) -> Result<()> {
+        if let Some(user) = self.state()?.users.get_mut(&user.username) {
+            user.streak += 1;
+        }
+
+        Ok(())
+    }
+
+    fn query_streak(&self, user: &models::User) -> Result<u64> {
+        self.state()?
+            .users
+            .get(&user.username)
+            .map(|u| u.streak)
+            .ok_or_else(|| anyhow!("User {} is not in the database.", user.username))
+    }
+
+    fn streak_break(&self, user: &models::User) -> Result<()> {
+        if let Some(user) = self.state()?.users.get_mut(&user.username) {
+            user.streak = 0;
+        }
+
+        Ok(())
+    }
+
+    fn query_streak_freezes(&self, user: &models::User) -> Result<u64> {
+        let state = self.state()?;
+        if !state.users.contains_key(&user.username) {
+            return Err(anyhow!("User {} is not in the database.", user.username));
+        }
+
+        Ok(state.streak_freezes.get(&user.username).copied().unwrap_or(0))
+    }
+
+    fn update_streak_freezes(&self, user: &models::User, freezes: u64) -> Result<()> {
+        let mut state = self.state()?;
+        if !state.users.contains_key(&user.username) {
+            return Ok(());
+        }
+
+        match freezes {
+            0 => state.streak_freezes.remove(&user.username),
+            freezes => state.streak_freezes.insert(user.username.clone(), freezes),
+        };
+
+        Ok(())
+    }
+
+    /////*============== PROBLEM QUERIES ==============*/
+    fn insert_problem(&self, problem: &models::Problem) -> Result<bool> {
+        let mut state = self.state()?;
+        if state.problems.contains_key(&problem.title) {
+            return Ok(false);
+        }
+
+        state.problems.insert(
+            problem.title.clone(),
+            models::Problem { url: problem_link(problem), ..problem.clone() },
+        );
+
+        Ok(true)
+    }
+
+    fn query_problem(&self, title: &str) -> Result<Option<models::Problem>> {
+        Ok(self.state()?.problems.get(title).cloned())
+    }
+
+    fn update_problem_difficulty(&self, title: &str, difficulty: &str) -> Result<()> {
+        if let Some(problem) = self.state()?.problems.get_mut(title) {
+            problem.difficulty = difficulty.to_owned();
+        }
+
+        Ok(())
+    }
+
+    fn query_problem_titles(&self, search: &str, limit: usize) -> Result<Vec<String>> {
+        let search = search.to_lowercase();
+        let mut titles = self
+            .state()?
+            .problems
+            .keys()
+            .filter(|title| title.to_lowercase().contains(&search))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        titles.sort();
+        titles.truncate(limit);
+        Ok(titles)
+    }
+
+    /////*============== GUILD QUERIES ==============*/
+    fn query_guilds(&self) -> Result<Vec<u64>> {
+        Ok(self.state()?.guild_settings.keys().copied().collect())
+    }
+
+    fn query_guild_settings(&self, guild_id: u64) -> Result<models::GuildSettings> {
+        Ok(self.state()?.guild_settings.get(&guild_id).cloned().unwrap_or_default())
+    }
+
+    fn update_guild_settings(&self, guild_id: u64, settings: &models::GuildSettings)
+        -> Result<()>
+    {
+        self.state()?.guild_settings.insert(guild_id, settings.clone());
+        Ok(())
+    }
+
+    fn claim_legacy_preferences(&self, _guild_id: u64) -> Result<usize> {
+        // There's never anything left over from before guilds in memory.
+        Ok(0)
+    }
+
+    /////*============== SEASON QUERIES ==============*/
+    fn archive_season(&self, guild_id: u64, season: &models::Season) -> Result<()> {
+        self.state()?.seasons.insert((guild_id, season.number), season.clone());
+        Ok(())
+    }
+
+    fn query_season(&self, guild_id: u64, number: u64) -> Result<Option<models::Season>> {
+        Ok(self.state()?.seasons.get(&(guild_id, number)).cloned())
+    }
+
+    /////*============== CHALLENGE QUERIES ==============*/
+    fn insert_challenge(&self, challenge: &models::Challenge) -> Result<u64> {
+        let mut state = self.state()?;
+        let id = state.challenges.len() as u64 + 1;
+        state.challenges.push(models::Challenge { id, ..challenge.clone() });
+
+        Ok(id)
+    }
+
+    fn query_open_challenges(&self) -> Result<Vec<models::Challenge>> {
+        Ok(self
+            .state()?
+            .challenges
+            .iter()
+            .filter(|challenge| challenge.outcome == models::ChallengeOutcome::Open)
+            .sorted_by_key(|challenge| challenge.deadline)
+            .cloned()
+            .collect())
+    }
+
+    fn resolve_challenge(&self, id: u64, outcome: models::ChallengeOutcome, resolved_at: usize)
+        -> Result<()>
+    {
+        let mut state = self.state()?;
+        if let Some(challenge) = state.challenges.iter_mut().find(|challenge| challenge.id == id) {
+            challenge.outcome = outcome;
+            challenge.resolved_at = Some(resolved_at);
+        }
+
+        Ok(())
+    }
+
+    fn query_challenge_record(&self, guild_id: u64, username: &str)
+        -> Result<models::ChallengeRecord>
+    {
+        use models::ChallengeOutcome::{Expired, Won};
+
+        let mut record = models::ChallengeRecord::default();
+        let state = self.state()?;
+        let challenges = state.challenges.iter().filter(|challenge| challenge.guild_id == guild_id);
+        for challenge in challenges {
+            match (challenge.outcome, challenge.challenged == username,
+                   challenge.challenger == username) {
+                (Won, true, _) | (Expired, _, true) => record.wins += 1,
+                (Expired, true, _) | (Won, _, true) => record.losses += 1,
+                _ => {}
+            }
+        }
+
+        Ok(record)
+    }
+
+    /////*============== LIST QUERIES ==============*/
+    fn replace_problem_list(&self, guild_id: u64, list: &models::ProblemList) -> Result<()> {
+        let list = models::ProblemList {
+            problems: list.problems.iter().unique().cloned().collect(),
+            ..list.clone()
+        };
+        self.state()?.problem_lists.insert((guild_id, list.name.clone()), list);
+
+        Ok(())
+    }
+
+    fn query_problem_list(&self, guild_id: u64, name: &str) -> Result<Option<models::ProblemList>> {
+        Ok(self.state()?.problem_lists.get(&(guild_id, name.to_owned())).cloned())
+    }
+
+    fn query_problem_list_names(&self, guild_id: u64) -> Result<Vec<String>> {
+        Ok(self
+            .state()?
+            .problem_lists
+            .keys()
+            .filter(|(guild, _)| *guild == guild_id)
+            .map(|(_, name)| name.clone())
+            .sorted()
+            .collect())
+    }
+
+    fn update_list_follow(&self, guild_id: u64, username: &str, name: &str, following: bool)
+        -> Result<()>
+    {
+        let mut state = self.state()?;
+        let follow = (guild_id, username.to_owned(), name.to_owned());
+        match following {
+            true => state.list_follows.insert(follow),
+            false => state.list_follows.remove(&follow),
+        };
+
+        Ok(())
+    }
+
+    fn query_followed_lists(&self, guild_id: u64, username: &str) -> Result<Vec<String>> {
+        Ok(self
+            .state()?
+            .list_follows
+            .iter()
+            .filter(|(guild, follower, _)| *guild == guild_id && follower == username)
+            .map(|(_, _, name)| name.clone())
+            .sorted()
+            .collect())
+    }
+
+    /////*============== REMINDER QUERIES ==============*/
+    fn update_streak_reminder(&self, reminder: &models::StreakReminder) -> Result<()> {
+        self.state()?.streak_reminders.insert(reminder.discord_id, reminder.clone());
+        Ok(())
+    }
+
+    fn delete_streak_reminder(&self, discord_id: u64) -> Result<()> {
+        self.state()?.streak_reminders.remove(&discord_id);
+        Ok(())
+    }
+
+    fn query_streak_reminder(&self, discord_id: u64) -> Result<Option<models::StreakReminder>> {
+        Ok(self.state()?.streak_reminders.get(&discord_id).cloned())
+    }
+
+    fn query_streak_reminders(&self) -> Result<Vec<models::StreakReminder>> {
+        Ok(self.state()?.streak_reminders.values().cloned().collect())
+    }
+
+    /////*============== KUDOS QUERIES ==============*/
+    fn insert_submission_announcement(&self, announcement: &models::SubmissionAnnouncement)
+        -> Result<()>
+    {
+        self.state()?
+            .submission_announcements
+            .entry(announcement.message_id)
+            .or_insert_with(|| announcement.clone());
+
+        Ok(())
+    }
+
+    fn query_submission_announcement(&self, message_id: u64)
+        -> Result<Option<models::SubmissionAnnouncement>>
+    {
+        Ok(self.state()?.submission_announcements.get(&message_id).cloned())
+    }
+
+    fn insert_kudos(&self, kudos: &models::Kudos) -> Result<()> {
+        self.state()?
+            .kudos
+            .entry((kudos.message_id, kudos.giver_id, kudos.emoji.clone()))
+            .or_insert_with(|| kudos.clone());
+
+        Ok(())
+    }
+
+    fn delete_kudos(&self, message_id: u64, giver_id: u64, emoji: &str) -> Result<()> {
+        self.state()?.kudos.remove(&(message_id, giver_id, emoji.to_owned()));
+        Ok(())
+    }
+
+    fn query_kudos_totals(&self, guild_id: u64, since: usize, until: usize)
+        -> Result<Vec<(String, u64)>>
+    {
+        let state = self.state()?;
+        let given = state
+            .kudos
+            .values()
+            .filter(|kudos| (since..until).contains(&kudos.given_at))
+            .filter_map(|kudos| {
+                let announcement = state.submission_announcements.get(&kudos.message_id)?;
+                (announcement.guild_id == guild_id)
+                    .then_some((announcement.username.clone(), kudos.message_id, kudos.giver_id))
+            })
+            .unique();
+
+        let mut totals = HashMap::<String, u64>::new();
+        for (username, _, _) in given {
+            *totals.entry(username).or_default() += 1;
+        }
+
+        Ok(totals
+            .into_iter()
+            .sorted_by(|(a, a_kudos), (b, b_kudos)| b_kudos.cmp(a_kudos).then(a.cmp(b)))
+            .collect())
+    }
+
+    /////*============== LINK QUERIES ==============*/
+    fn link_account(&self, discord_id: u64, username: &str) -> Result<()> {
+        let mut state = self.state()?;
+        let verified = state
+            .links
+            .get(&discord_id)
+            .is_some_and(|(linked, verified)| *verified && linked == username);
+
+        state.links.insert(discord_id, (username.to_owned(), verified));
+        Ok(())
+    }
+
+    fn query_linked_username(&self, discord_id: u64) -> Result<Option<String>> {
+        Ok(self.state()?.links.get(&discord_id).map(|(username, _)| username.clone()))
+    }
+
+    fn is_verified_owner(&self, discord_id: u64, username: &str) -> Result<bool> {
+        Ok(self
+            .state()?
+            .links
+            .get(&discord_id)
+            .is_some_and(|(linked, verified)| *verified && linked.eq_ignore_ascii_case(username)))
+    }
+
+    /////*============== VERIFICATION QUERIES ==============*/
+    fn insert_verification(&self, discord_id: u64, username: &str, token: &str) -> Result<()> {
+        self.state()?.verifications.insert(discord_id, models::Verification {
+            username: username.to_owned(),
+            token: token.to_owned(),
+            issued_at: current_timestamp(),
+        });
+
+        Ok(())
+    }
+
+    fn query_verification(&self, discord_id: u64) -> Result<Option<models::Verification>> {
+        let current_timestamp = current_timestamp();
+        Ok(self
+            .state()?
+            .verifications
+            .get(&discord_id)
+            .filter(|v| {
+                current_timestamp.saturating_sub(v.issued_at) < models::VERIFICATION_THRESHOLD
+            })
+            .cloned())
+    }
+
+    fn complete_verification(&self, discord_id: u64) -> Result<()> {
+        let mut state = self.state()?;
+        if let Some(verification) = state.verifications.remove(&discord_id) {
+            state.links.insert(discord_id, (verification.username, true));
+        }
+
+        Ok(())
+    }
+}
+
+/// The same scenarios, run against both stores: `MemoryStore` has to behave like the SQL.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcdb::SqliteStore;
+
+    use rusqlite::Connection;
+
+    const HOUR_IN_MILLIS: usize = DAY_IN_MILLIS / 24;
+
+    /// Runs `scenario` against a `SqliteStore` (on a database that only lives in memory, named
+    /// `name`), then against a `MemoryStore`. It's told which one it's running against.
+    fn for_each_store(name: &str, scenario: impl Fn(&str, &dyn Store)) {
+        // The database is gone once its last connection closes.
+        let path = format!("file:{name}?mode=memory&cache=shared");
+        let _keeper = Connection::open(&path).unwrap();
+        let sqlite = SqliteStore::new(&path);
+        sqlite.initialize_db().unwrap();
+
+        scenario("sqlite", &sqlite);
+        scenario("memory", &MemoryStore::new());
+    }
+
+    fn user(username: &str) -> models::User {
+        models::User {
+            username: username.to_owned(),
+            easy_solved: 0,
+            medium_solved: 0,
+            hard_solved: 0,
+            total_solved: 0,
+            ranking: 0,
+            streak: 0,
+        }
+    }
+
+    fn submit(store: &dyn Store, username: &str, title: &str, timestamp: usize, accepted: bool) {
+        let problem = models::Problem {
+            title: title.to_owned(),
+            url: title.to_lowercase().replace(' ', "-"),
+            difficulty: String::from("Easy"),
+        };
+        store.insert_problem(&problem).unwrap();
+        store
+            .insert_submission(&models::Submission {
+                problem,
+                username: username.to_owned(),
+                language: String::from("rust"),
+                timestamp,
+                accepted,
+                url: format!("https://leetcode.com/submissions/detail/{timestamp}/"),
+            })
+            .unwrap();
+    }
+
+    fn usernames(users: Vec<models::User>) -> Vec<String> {
+        users.into_iter().map(|user| user.username).sorted().collect()
+    }
+
+    #[test]
+    fn tracking() {
+        for_each_store("tracking", |name, store| {
+            let (alice, bob) = (user("alice"), user("bob"));
+            store.track_user(1, &alice).unwrap();
+            store.track_user(2, &alice).unwrap();
+            store.track_user(1, &bob).unwrap();
+            store.untrack_user(1, &bob).unwrap();
+
+            assert!(store.is_tracked(1, &alice).unwrap(), "{name}");
+            assert!(!store.is_tracked(1, &bob).unwrap(), "{name}");
+            // Untracking keeps the preferences around.
+            let prefs = store.query_user_preferences(1, &bob).unwrap();
+            assert!(prefs.is_some_and(|prefs| !prefs.tracked), "{name}");
+
+            assert_eq!(usernames(store.query_tracked_users().unwrap()), ["alice"], "{name}");
+            assert_eq!(usernames(store.query_guild_tracked_users(1).unwrap()), ["alice"],
+                       "{name}");
+            let guilds = store
+                .query_tracking_guilds(&alice)
+                .unwrap()
+                .into_iter()
+                .map(|(guild_id, _)| guild_id)
+                .sorted()
+                .collect::<Vec<_>>();
+            assert_eq!(guilds, [1, 2], "{name}");
+        });
+    }
+
+    #[test]
+    fn activity_and_streaks() {
+        let now = current_timestamp();
+        for_each_store("activity_and_streaks", |name, store| {
+            let [alice, bob, carol, dave] = ["alice", "bob", "carol", "dave"].map(user);
+            for user in [&alice, &bob, &carol] {
+                store.track_user(1, user).unwrap();
+            }
+            store.untrack_user(1, &dave).unwrap();
+
+            submit(store, "alice", "Two Sum", now - HOUR_IN_MILLIS, true);
+            submit(store, "bob", "Two Sum", now - 25 * HOUR_IN_MILLIS, true);
+            submit(store, "bob", "Add Two Numbers", now - HOUR_IN_MILLIS, false);
+            submit(store, "dave", "Two Sum", now - HOUR_IN_MILLIS, true);
+
+            assert!(store.is_active(&alice).unwrap(), "{name}");
+            assert!(!store.is_active(&bob).unwrap(), "{name}");
+            assert!(!store.is_active(&carol).unwrap(), "{name}");
+            assert!(!store.is_active(&dave).unwrap(), "{name}");
+
+            store.streak_increment(&alice).unwrap();
+            store.streak_increment(&alice).unwrap();
+            assert_eq!(store.query_streak(&alice).unwrap(), 2, "{name}");
+            store.streak_break(&alice).unwrap();
+            assert_eq!(store.query_streak(&alice).unwrap(), 0, "{name}");
+        });
+    }
+
+    #[test]
+    fn submissions_between() {
+        for_each_store("submissions_between", |name, store| {
+            let alice = user("alice");
+            store.track_user(1, &alice).unwrap();
+            submit(store, "alice", "Two Sum", 1000, true);
+            submit(store, "alice", "Add Two Numbers", 2000, false);
+            submit(store, "alice", "Add Two Numbers", 3000, true);
+            submit(store, "alice", "Valid Parentheses", 4000, true);
+
+            let between = store
+                .query_submissions_between(&alice, 1500, 4000)
+                .unwrap()
+                .into_iter()
+                .map(|submission| submission.timestamp)
+                .collect::<Vec<_>>();
+            assert_eq!(between, [3000, 2000], "{name}");
+
+            let solved = store.query_solved_before(&alice, 3500).unwrap();
+            assert_eq!(solved.into_iter().sorted().collect::<Vec<_>>(),
+                       ["Add Two Numbers", "Two Sum"], "{name}");
+        });
+    }
+
+    #[test]
+    fn challenges() {
+        let challenge = |guild_id, challenger: &str, challenged: &str, deadline| models::Challenge {
+            id: 0,
+            guild_id,
+            challenger: challenger.to_owned(),
+            challenged: challenged.to_owned(),
+            problem: String::from("Two Sum"),
+            issued_at: 0,
+            deadline,
+            outcome: models::ChallengeOutcome::Open,
+            resolved_at: None,
+        };
+        let deadlines = |store: &dyn Store| -> Vec<usize> {
+            store
+                .query_open_challenges()
+                .unwrap()
+                .into_iter()
+                .map(|challenge| challenge.deadline)
+                .collect()
+        };
+
+        for_each_store("challenges", |name, store| {
+            store.insert_challenge(&challenge(1, "alice", "bob", 300)).unwrap();
+            let won = store.insert_challenge(&challenge(1, "alice", "bob", 100)).unwrap();
+            let expired = store.insert_challenge(&challenge(1, "bob", "alice", 200)).unwrap();
+            store.insert_challenge(&challenge(2, "carol", "alice", 50)).unwrap();
+
+            // Expiring challenges relies on the soonest deadlines coming first.
+            assert_eq!(deadlines(store), [50, 100, 200, 300], "{name}");
+
+            store.resolve_challenge(won, models::ChallengeOutcome::Won, 90).unwrap();
+            store.resolve_challenge(expired, models::ChallengeOutcome::Expired, 200).unwrap();
+            assert_eq!(deadlines(store), [50, 300], "{name}");
+
+            let record = |guild_id, username| {
+                store.query_challenge_record(guild_id, username).unwrap().to_string()
+            };
+            assert_eq!(record(1, "bob"), "2W-0L", "{name}");
+            assert_eq!(record(1, "alice"), "0W-2L", "{name}");
+            assert_eq!(record(2, "alice"), "0W-0L", "{name}");
+        });
+    }
+
+    #[test]
+    fn streak_freezes() {
+        for_each_store("streak_freezes", |name, store| {
+            let alice = user("alice");
+            store.track_user(1, &alice).unwrap();
+
+            assert_eq!(store.query_streak_freezes(&alice).unwrap(), 0, "{name}");
+            store.update_streak_freezes(&alice, 2).unwrap();
+            assert_eq!(store.query_streak_freezes(&alice).unwrap(), 2, "{name}");
+            store.update_streak_freezes(&alice, 0).unwrap();
+            assert_eq!(store.query_streak_freezes(&alice).unwrap(), 0, "{name}");
+
+            // Users who aren't in the database have no freezes to speak of.
+            assert!(store.query_streak_freezes(&user("bob")).is_err(), "{name}");
+        });
+    }
+
+    #[test]
+    fn kudos() {
+        let announcement = |message_id, guild_id, username: &str| {
+            models::SubmissionAnnouncement { message_id, guild_id, username: username.to_owned() }
+        };
+        let kudos = |message_id, giver_id, emoji: &str, given_at| models::Kudos {
+            message_id,
+            giver_id,
+            emoji: emoji.to_owned(),
+            given_at,
+        };
+        let totals = |pairs: &[(&str, u64)]| -> Vec<(String, u64)> {
+            pairs.iter().map(|(username, kudos)| (username.to_string(), *kudos)).collect()
+        };
+
+        for_each_store("kudos", |name, store| {
+            store.insert_submission_announcement(&announcement(1, 10, "alice")).unwrap();
+            // Recording the same message again (e.g. a digest) changes nothing.
+            store.insert_submission_announcement(&announcement(1, 10, "bob")).unwrap();
+            store.insert_submission_announcement(&announcement(2, 10, "bob")).unwrap();
+            store.insert_submission_announcement(&announcement(3, 11, "bob")).unwrap();
+
+            let first = store.query_submission_announcement(1).unwrap();
+            assert_eq!(first.map(|a| a.username).as_deref(), Some("alice"), "{name}");
+            assert!(store.query_submission_announcement(9).unwrap().is_none(), "{name}");
+
+            // However many emojis a member reacts with, they give one kudos per announcement.
+            store.insert_kudos(&kudos(1, 100, "🔥", 5)).unwrap();
+            store.insert_kudos(&kudos(1, 100, "👏", 6)).unwrap();
+            store.insert_kudos(&kudos(1, 101, "🔥", 7)).unwrap();
+            store.insert_kudos(&kudos(2, 100, "🔥", 8)).unwrap();
+            store.insert_kudos(&kudos(3, 100, "🔥", 8)).unwrap();
+
+            assert_eq!(store.query_kudos_totals(10, 0, 100).unwrap(),
+                       totals(&[("alice", 2), ("bob", 1)]), "{name}");
+            assert_eq!(store.query_kudos_totals(10, 7, 100).unwrap(),
+                       totals(&[("alice", 1), ("bob", 1)]), "{name}");
+
+            store.delete_kudos(1, 101, "🔥").unwrap();
+            store.delete_kudos(1, 100, "🔥").unwrap();
+            assert_eq!(store.query_kudos_totals(10, 0, 100).unwrap(),
+                       totals(&[("alice", 1), ("bob", 1)]), "{name}");
+            assert_eq!(store.query_kudos_totals(11, 0, 100).unwrap(), totals(&[("bob", 1)]),
+                       "{name}");
+        });
+    }
+
+    #[test]
+    fn verified_owner() {
+        for_each_store("verified_owner", |name, store| {
+            store.link_account(1, "alice").unwrap();
+            assert!(!store.is_verified_owner(1, "alice").unwrap(), "{name}");
+
+            store.insert_verification(1, "alice", "token").unwrap();
+            store.complete_verification(1).unwrap();
+            assert!(store.is_verified_owner(1, "alice").unwrap(), "{name}");
+            assert!(store.is_verified_owner(1, "Alice").unwrap(), "{name}");
+            assert!(!store.is_verified_owner(1, "bob").unwrap(), "{name}");
+            assert!(!store.is_verified_owner(2, "alice").unwrap(), "{name}");
+        });
+    }
+}
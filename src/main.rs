@@ -4,6 +4,8 @@ use leekbot::lcdb;
 use anyhow::Context;
 use dotenv::dotenv;
 
+use std::sync::Arc;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Begin logger
@@ -11,10 +13,11 @@ async fn main() -> anyhow::Result<()> {
     env_logger::Builder::from_env("LOG_LEVEL").init();
 
     // Initialize database
-    lcdb::initialize_db().context("Error initializing database.")?;
+    let store = lcdb::SqliteStore::new("db/leek.db");
+    store.initialize_db().context("Error initializing database.")?;
 
     // Run the discord bot
-    lcbot::run_leekbot()
+    lcbot::run_leekbot(Arc::new(store))
         .await
         .context("Error initializing discord bot.")?;
 
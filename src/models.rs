@@ -1,9 +1,35 @@
-use chrono::DateTime;
+use chrono::{DateTime, Datelike, Months, TimeDelta, Timelike, Utc};
+use itertools::Itertools;
+use serenity::builder::{CreateEmbed, CreateEmbedFooter};
 use std::time::Duration;
 
 // Threshold for a problem to be considered 'recent' is 8 hours, or 28800 seconds
 pub const RECENT_THRESHOLD: usize = Duration::new(28800, 0).as_millis() as usize;
 
+// Verification tokens are good for 1 hour, or 3600 seconds
+pub const VERIFICATION_THRESHOLD: usize = Duration::new(3600, 0).as_millis() as usize;
+
+// Users who want their submissions digested get them merged for 10 minutes, or 600 seconds,
+// unless their guild digests everyone's over a window of its own.
+pub const DEFAULT_DIGEST_WINDOW: Duration = Duration::from_secs(600);
+
+/// The difficulty of problems we haven't looked up yet.
+pub const UNKNOWN_DIFFICULTY: &str = "NULL";
+
+// Embed colors: LeetCode's own, and its colors for each difficulty.
+pub const LEETCODE_COLOR: u32 = 0xFFA116;
+const EASY_COLOR: u32 = 0x00B8A3;
+const MEDIUM_COLOR: u32 = 0xFFC01E;
+const HARD_COLOR: u32 = 0xFF375F;
+const UNKNOWN_COLOR: u32 = 0x808080;
+
+/// Something that can be shown on Discord: as an embed, or as plain (markdown) text wherever
+/// embeds can't be sent.
+pub trait Render {
+    fn render_text(&self) -> String;
+    fn render_embed(&self) -> CreateEmbed;
+}
+
 #[derive(Debug, Clone)]
 pub struct User {
     pub username: String,
@@ -49,6 +75,10 @@ pub struct UserPreferences {
 pub struct AnnouncementPreferences {
     pub announce_failures: bool,
     pub has_submission_link: bool,
+
+    /// Whether bursts of submissions are merged into one announcement, even if the guild doesn't
+    /// digest everyone's. See `GuildSettings::digest_window`.
+    pub digest: bool,
 }
 
 impl Default for UserPreferences {
@@ -59,11 +89,267 @@ pub const DEFAULT_USER_PREFERENCES: UserPreferences = UserPreferences {
     tracked: true,
     announcement: Some(AnnouncementPreferences {
         has_submission_link: true,
-        announce_failures: false
+        announce_failures: false,
+        digest: false,
     }),
 };
 
-#[derive(Debug)]
+/// A pending proof that a Discord member owns a LeetCode account: they have to put `token` in the
+/// account's "About me".
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub username: String,
+    pub token: String,
+    pub issued_at: usize,
+}
+
+/// Users earn a streak freeze every `STREAK_FREEZE_DAYS` days of streak, and can save up to
+/// `MAX_STREAK_FREEZES` of them. One is spent (instead of losing the streak) on a missed day.
+pub const STREAK_FREEZE_DAYS: u64 = 7;
+pub const MAX_STREAK_FREEZES: u64 = 2;
+
+/// A member's request to be reminded when their streak is about to be lost.
+#[derive(Debug, Clone)]
+pub struct StreakReminder {
+    pub discord_id: u64,
+    /// How many hours (from 1 to 23) before streaks are checked at midnight UTC to remind them.
+    pub hours_before: u32,
+    /// Where to ping them, or None to DM them instead.
+    pub channel_id: Option<u64>,
+}
+
+impl StreakReminder {
+    /// The hour (UTC) the reminder is sent at.
+    pub fn hour(&self) -> u32 {
+        (24 - self.hours_before % 24) % 24
+    }
+
+    /// Whether the reminder is due at `now`, which is expected to be on the hour.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        now.hour() == self.hour()
+    }
+}
+
+/// Per-guild (Discord server) settings.
+#[derive(Debug, Clone, Default)]
+pub struct GuildSettings {
+    /// Where announcements are posted, unless their kind has a channel of its own below.
+    /// Nothing is announced if unset.
+    pub announcements_channel: Option<u64>,
+
+    /// Where each kind of announcement is posted instead of `announcements_channel`, if set.
+    /// See `AnnouncementKind`.
+    pub accepted_channel: Option<u64>,
+    pub failures_channel: Option<u64>,
+    pub streaks_channel: Option<u64>,
+    pub updates_channel: Option<u64>,
+    pub recaps_channel: Option<u64>,
+    pub seasons_channel: Option<u64>,
+    pub challenges_channel: Option<u64>,
+    pub goals_channel: Option<u64>,
+
+    /// Members with this role may run admin-only commands, on top of those who can manage the
+    /// server.
+    pub admin_role: Option<u64>,
+
+    /// What messages have to start with to be taken as commands. Falls back to $BOT_CALL_TOKEN.
+    pub prefix: Option<String>,
+
+    /// If set, a user's submissions announced within this many seconds of each other are merged
+    /// into one message, edited in place as more come in.
+    pub digest_window: Option<u64>,
+
+    /// Which recaps are posted, and when: at `recap_hour` (UTC) every day, and on `recap_day` (0
+    /// for Monday) every week.
+    pub daily_recap: bool,
+    pub weekly_recap: bool,
+    pub recap_hour: u32,
+    pub recap_day: u32,
+
+    /// How many points a solve of each difficulty is worth on the leaderboard. Falls back to
+    /// `DEFAULT_POINTS`.
+    pub easy_points: Option<u64>,
+    pub medium_points: Option<u64>,
+    pub hard_points: Option<u64>,
+
+    /// The leaderboard message kept up to date after every solve, if any: where it is, and which
+    /// period it ranks.
+    pub leaderboard_channel: Option<u64>,
+    pub leaderboard_message: Option<u64>,
+    pub leaderboard_period: Option<String>,
+
+    /// How long seasons last (see `SeasonLength`); no seasons are run if unset.
+    pub season_length: Option<String>,
+    /// The current season's number, counting from 1, and when (in milliseconds) it started.
+    pub season_number: u64,
+    pub season_start: Option<usize>,
+
+    /// The weekly group goal, if any: how many solves of `goal_difficulty` (or of any difficulty,
+    /// if unset) tracked users are after together each week.
+    pub goal_count: Option<u64>,
+    pub goal_difficulty: Option<String>,
+    /// The last milestone of the goal (in percent) that was announced, and when (in milliseconds).
+    pub goal_milestone: u64,
+    pub goal_milestone_at: Option<usize>,
+}
+
+// Easy, medium and hard solves are worth 1, 3 and 5 points, unless a guild says otherwise.
+pub const DEFAULT_POINTS: [u64; 3] = [1, 3, 5];
+
+impl GuildSettings {
+    /// The points a solve of `problem` is worth. Problems of unknown difficulty count as easy.
+    pub fn points(&self, problem: &Problem) -> u64 {
+        let [easy, medium, hard] = self.point_weights();
+        match problem.difficulty.as_str() {
+            "Medium" => medium,
+            "Hard" => hard,
+            _ => easy,
+        }
+    }
+
+    /// The points easy, medium and hard solves are worth.
+    pub fn point_weights(&self) -> [u64; 3] {
+        let [easy, medium, hard] = DEFAULT_POINTS;
+        [
+            self.easy_points.unwrap_or(easy),
+            self.medium_points.unwrap_or(medium),
+            self.hard_points.unwrap_or(hard),
+        ]
+    }
+
+    /// Where announcements of `kind` are posted, if anywhere.
+    pub fn channel(&self, kind: AnnouncementKind) -> Option<u64> {
+        self.channel_override(kind).or(self.announcements_channel)
+    }
+
+    /// Where announcements of `kind` are posted instead of `announcements_channel`, if set.
+    pub fn channel_override(&self, kind: AnnouncementKind) -> Option<u64> {
+        match kind {
+            AnnouncementKind::Accepted => self.accepted_channel,
+            AnnouncementKind::Failures => self.failures_channel,
+            AnnouncementKind::Streaks => self.streaks_channel,
+            AnnouncementKind::Updates => self.updates_channel,
+            AnnouncementKind::Recaps => self.recaps_channel,
+            AnnouncementKind::Seasons => self.seasons_channel,
+            AnnouncementKind::Challenges => self.challenges_channel,
+            AnnouncementKind::Goals => self.goals_channel,
+        }
+    }
+
+    /// How long a user's announcements keep being merged into one, if they are at all.
+    /// Guilds can digest everyone's submissions; users can ask for theirs to be digested anywhere.
+    pub fn digest_window(&self, announcement: &AnnouncementPreferences) -> Option<Duration> {
+        match (self.digest_window, announcement.digest) {
+            (Some(seconds), _) => Some(Duration::from_secs(seconds)),
+            (None, true) => Some(DEFAULT_DIGEST_WINDOW),
+            (None, false) => None,
+        }
+    }
+
+    pub fn set_channel_override(&mut self, kind: AnnouncementKind, channel: Option<u64>) {
+        match kind {
+            AnnouncementKind::Accepted => self.accepted_channel = channel,
+            AnnouncementKind::Failures => self.failures_channel = channel,
+            AnnouncementKind::Streaks => self.streaks_channel = channel,
+            AnnouncementKind::Updates => self.updates_channel = channel,
+            AnnouncementKind::Recaps => self.recaps_channel = channel,
+            AnnouncementKind::Seasons => self.seasons_channel = channel,
+            AnnouncementKind::Challenges => self.challenges_channel = channel,
+            AnnouncementKind::Goals => self.goals_channel = channel,
+        }
+    }
+
+    /// The current season's length, and when (in milliseconds) it started, if seasons are run.
+    pub fn current_season(&self) -> Option<(SeasonLength, usize)> {
+        let length = self.season_length.as_deref().and_then(SeasonLength::from_name)?;
+        Some((length, self.season_start?))
+    }
+
+    /// The recaps due to be posted at `now`, which is expected to be on the hour.
+    pub fn due_recaps(&self, now: DateTime<Utc>) -> Vec<RecapPeriod> {
+        if now.hour() != self.recap_hour {
+            return Vec::new();
+        }
+
+        let mut due = Vec::new();
+        if self.daily_recap {
+            due.push(RecapPeriod::Daily);
+        }
+        if self.weekly_recap && now.weekday().num_days_from_monday() == self.recap_day {
+            due.push(RecapPeriod::Weekly);
+        }
+
+        due
+    }
+}
+
+/// What the bot announces. Each kind can be posted to a channel of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnouncementKind {
+    /// Accepted submissions.
+    Accepted,
+    /// Failed submissions, for users who want them announced.
+    Failures,
+    /// Daily streak reports.
+    Streaks,
+    /// Notices that the bot was updated.
+    Updates,
+    /// Daily and weekly recaps.
+    Recaps,
+    /// Season winners, at the end of each season.
+    Seasons,
+    /// Challenges that were won, or that expired.
+    Challenges,
+    /// Milestones of the weekly group goal.
+    Goals,
+}
+
+impl AnnouncementKind {
+    pub const ALL: [AnnouncementKind; 8] = [
+        AnnouncementKind::Accepted,
+        AnnouncementKind::Failures,
+        AnnouncementKind::Streaks,
+        AnnouncementKind::Updates,
+        AnnouncementKind::Recaps,
+        AnnouncementKind::Seasons,
+        AnnouncementKind::Challenges,
+        AnnouncementKind::Goals,
+    ];
+
+    /// The kind's name, as given to `setchannel`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnnouncementKind::Accepted => "accepted",
+            AnnouncementKind::Failures => "failures",
+            AnnouncementKind::Streaks => "streaks",
+            AnnouncementKind::Updates => "updates",
+            AnnouncementKind::Recaps => "recaps",
+            AnnouncementKind::Seasons => "seasons",
+            AnnouncementKind::Challenges => "challenges",
+            AnnouncementKind::Goals => "goals",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// What's announced, as shown to members.
+    pub fn description(&self) -> &'static str {
+        match self {
+            AnnouncementKind::Accepted => "Accepted solves",
+            AnnouncementKind::Failures => "Failures",
+            AnnouncementKind::Streaks => "Streak reports",
+            AnnouncementKind::Updates => "Bot updates",
+            AnnouncementKind::Recaps => "Recaps",
+            AnnouncementKind::Seasons => "Season winners",
+            AnnouncementKind::Challenges => "Challenge results",
+            AnnouncementKind::Goals => "Group goal milestones",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Submission {
     pub problem: Problem,
 
@@ -75,7 +361,7 @@ pub struct Submission {
     pub url: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Problem {
     pub title: String,
     pub url: String,
@@ -101,3 +387,816 @@ impl std::fmt::Display for Submission {
         )
     }
 }
+
+impl Problem {
+    /// The problem's page on LeetCode. `url` is only the slug until the problem is stored.
+    pub fn link(&self) -> String {
+        match self.url.starts_with("http") {
+            true => self.url.clone(),
+            false => format!("https://leetcode.com/problems/{}", self.url),
+        }
+    }
+
+    /// Easy problems are teal, medium ones yellow and hard ones red.
+    pub fn color(&self) -> u32 {
+        match self.difficulty.as_str() {
+            "Easy" => EASY_COLOR,
+            "Medium" => MEDIUM_COLOR,
+            "Hard" => HARD_COLOR,
+            _ => UNKNOWN_COLOR,
+        }
+    }
+
+    pub fn is_difficulty_known(&self) -> bool {
+        matches!(self.difficulty.as_str(), "Easy" | "Medium" | "Hard")
+    }
+}
+
+impl Render for User {
+    fn render_text(&self) -> String {
+        self.to_string()
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        CreateEmbed::new()
+            .title(&self.username)
+            .url(format!("https://leetcode.com/u/{}/", self.username))
+            .color(LEETCODE_COLOR)
+            .field("Easy", self.easy_solved.to_string(), true)
+            .field("Medium", self.medium_solved.to_string(), true)
+            .field("Hard", self.hard_solved.to_string(), true)
+            .field("Total", self.total_solved.to_string(), true)
+            .field("Ranking", self.ranking.to_string(), true)
+            .field("Streak", self.streak.to_string(), true)
+    }
+}
+
+impl Render for Submission {
+    fn render_text(&self) -> String {
+        self.to_string()
+    }
+
+    /// The problem (linked, and colored by difficulty), whether it was accepted, when (relative to
+    /// whoever's looking) and in which language. The submission itself isn't linked: not everyone
+    /// wants theirs shown.
+    fn render_embed(&self) -> CreateEmbed {
+        let verdict = if self.accepted { "✅ Accepted" } else { "❌ Not accepted" };
+        let mut embed = CreateEmbed::new()
+            .title(&self.problem.title)
+            .url(self.problem.link())
+            .color(self.problem.color())
+            .description(format!("{verdict} <t:{}:R>", self.timestamp / 1000))
+            .field("Language", format!("`{}`", self.language), true);
+
+        if self.problem.is_difficulty_known() {
+            embed = embed.field("Difficulty", &self.problem.difficulty, true);
+        }
+
+        embed
+    }
+}
+
+/// How far back a recap looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecapPeriod {
+    Daily,
+    Weekly,
+}
+
+impl RecapPeriod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecapPeriod::Daily => "daily",
+            RecapPeriod::Weekly => "weekly",
+        }
+    }
+
+    /// How long the period is, in milliseconds.
+    pub fn length(&self) -> usize {
+        match self {
+            RecapPeriod::Daily => Duration::from_secs(86400).as_millis() as usize,
+            RecapPeriod::Weekly => Duration::from_secs(7 * 86400).as_millis() as usize,
+        }
+    }
+}
+
+/// What a guild's tracked users got up to over a period.
+#[derive(Debug, Clone)]
+pub struct Recap {
+    pub period: RecapPeriod,
+    /// Timestamps (in milliseconds) of when the period started and ended.
+    pub since: usize,
+    pub until: usize,
+
+    /// Everyone who submitted anything, most solves first.
+    pub users: Vec<RecapUser>,
+
+    /// The problem submitted the most, and how many times it was.
+    pub most_attempted: Option<(Problem, usize)>,
+    /// Whoever has the longest current streak, and how long it is.
+    pub longest_streak: Option<(String, u64)>,
+    /// Whoever got the most kudos over the period, and how many.
+    pub most_cheered: Option<(String, u64)>,
+}
+
+/// What one user got up to over a recap's period.
+#[derive(Debug, Clone, Default)]
+pub struct RecapUser {
+    pub username: String,
+
+    /// Distinct problems solved, by difficulty.
+    pub easy_solved: usize,
+    pub medium_solved: usize,
+    pub hard_solved: usize,
+    /// Distinct problems solved whose difficulty we don't know.
+    pub other_solved: usize,
+
+    /// How many of the problems solved were solved for the first time (the rest are re-solves).
+    pub new_solved: usize,
+    pub submissions: usize,
+}
+
+impl RecapUser {
+    pub fn total_solved(&self) -> usize {
+        self.easy_solved + self.medium_solved + self.hard_solved + self.other_solved
+    }
+
+    /// e.g. "**4 solved** (1 easy, 2 medium, 1 hard): 3 new, 1 re-solved, in 6 submissions"
+    fn summary(&self) -> String {
+        format!(
+            "**{} solved** ({} easy, {} medium, {} hard): {} new, {} re-solved, in {} submission{}",
+            self.total_solved(),
+            self.easy_solved,
+            self.medium_solved,
+            self.hard_solved,
+            self.new_solved,
+            self.total_solved() - self.new_solved,
+            self.submissions,
+            if self.submissions == 1 { "" } else { "s" }
+        )
+    }
+}
+
+impl Recap {
+    fn title(&self) -> String {
+        match self.period {
+            RecapPeriod::Daily => String::from("Daily recap"),
+            RecapPeriod::Weekly => String::from("Weekly recap"),
+        }
+    }
+
+    /// When the period ran, as Discord timestamps.
+    fn timespan(&self) -> String {
+        format!("<t:{}:f> to <t:{}:f>", self.since / 1000, self.until / 1000)
+    }
+}
+
+// Embeds can't have more than 25 fields: leave room for the highlights.
+const MAX_RECAP_USERS: usize = 20;
+
+impl Render for Recap {
+    fn render_text(&self) -> String {
+        let mut output = format!("**{}** ({})\n", self.title(), self.timespan());
+        if self.users.is_empty() {
+            output += "Nobody submitted anything.\n";
+        }
+        for user in &self.users {
+            output += &format!("\t{}: {}\n", user.username, user.summary());
+        }
+
+        if let Some((problem, attempts)) = &self.most_attempted {
+            output += &format!("Most attempted: [{}]({}), {attempts} times\n",
+                               problem.title, problem.link());
+        }
+        if let Some((username, streak)) = &self.longest_streak {
+            output += &format!("Longest streak: {username}, {streak} days\n");
+        }
+        if let Some((username, kudos)) = &self.most_cheered {
+            output += &format!("Most cheered: {username}, {kudos} kudos\n");
+        }
+
+        output
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        let mut description = self.timespan();
+        if self.users.is_empty() {
+            description += "\nNobody submitted anything.";
+        }
+
+        let mut embed = CreateEmbed::new()
+            .title(self.title())
+            .description(description)
+            .color(LEETCODE_COLOR);
+
+        for user in self.users.iter().take(MAX_RECAP_USERS) {
+            embed = embed.field(&user.username, user.summary(), false);
+        }
+        if self.users.len() > MAX_RECAP_USERS {
+            let more = self.users.len() - MAX_RECAP_USERS;
+            embed = embed.footer(CreateEmbedFooter::new(format!("...and {more} more")));
+        }
+
+        if let Some((problem, attempts)) = &self.most_attempted {
+            embed = embed.field("Most attempted",
+                                format!("[{}]({}), {attempts} times", problem.title, problem.link()),
+                                true);
+        }
+        if let Some((username, streak)) = &self.longest_streak {
+            embed = embed.field("Longest streak", format!("{username}, {streak} days"), true);
+        }
+        if let Some((username, kudos)) = &self.most_cheered {
+            embed = embed.field("Most cheered", format!("{username}, {kudos} kudos"), true);
+        }
+
+        embed
+    }
+}
+
+/// How far back a leaderboard looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardPeriod {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [LeaderboardPeriod; 4] = [
+        LeaderboardPeriod::Day,
+        LeaderboardPeriod::Week,
+        LeaderboardPeriod::Month,
+        LeaderboardPeriod::All,
+    ];
+
+    /// The period's name, as given to `leaderboard`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Day => "day",
+            LeaderboardPeriod::Week => "week",
+            LeaderboardPeriod::Month => "month",
+            LeaderboardPeriod::All => "all",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|period| period.name() == name)
+    }
+
+    /// How long the period is, in milliseconds. None if it goes back to the beginning.
+    pub fn length(&self) -> Option<usize> {
+        let days = match self {
+            LeaderboardPeriod::Day => 1,
+            LeaderboardPeriod::Week => 7,
+            LeaderboardPeriod::Month => 30,
+            LeaderboardPeriod::All => return None,
+        };
+
+        Some(Duration::from_secs(days * 86400).as_millis() as usize)
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Day => "Leaderboard: last 24 hours",
+            LeaderboardPeriod::Week => "Leaderboard: last 7 days",
+            LeaderboardPeriod::Month => "Leaderboard: last 30 days",
+            LeaderboardPeriod::All => "Leaderboard: all time",
+        }
+    }
+}
+
+/// A guild's tracked users, ranked by the points their solves are worth.
+#[derive(Debug, Clone)]
+pub struct Leaderboard {
+    pub period: LeaderboardPeriod,
+    /// The points easy, medium and hard solves were worth.
+    pub weights: [u64; 3],
+    /// Best first.
+    pub entries: Vec<LeaderboardEntry>,
+    /// When it was computed, in milliseconds.
+    pub updated_at: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LeaderboardEntry {
+    pub username: String,
+    pub points: u64,
+    /// Distinct problems solved.
+    pub solved: usize,
+    /// When (in milliseconds) they got to their points. Ties go to whoever got there first.
+    pub reached_at: usize,
+}
+
+// Leaderboards show the top 20.
+const MAX_LEADERBOARD_ENTRIES: usize = 20;
+
+/// One line per entry (up to `MAX_LEADERBOARD_ENTRIES`), with medals for the top three.
+fn standings_lines(entries: &[LeaderboardEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .take(MAX_LEADERBOARD_ENTRIES)
+        .enumerate()
+        .map(|(i, entry)| {
+            let rank = match i {
+                0 => String::from("🥇"),
+                1 => String::from("🥈"),
+                2 => String::from("🥉"),
+                _ => format!("`#{}`", i + 1),
+            };
+            let plural = if entry.points == 1 { "" } else { "s" };
+            format!("{rank} **{}**: {} point{plural} ({} solved)",
+                    entry.username, entry.points, entry.solved)
+        })
+        .collect()
+}
+
+impl Leaderboard {
+    fn footer(&self) -> String {
+        let [easy, medium, hard] = self.weights;
+        format!("Easy {easy}, medium {medium}, hard {hard} points")
+    }
+}
+
+impl Render for Leaderboard {
+    fn render_text(&self) -> String {
+        let mut output = format!("**{}**\n", self.period.title());
+        if self.entries.is_empty() {
+            output += "Nobody has solved anything yet.\n";
+        }
+        for line in standings_lines(&self.entries) {
+            output += &format!("{line}\n");
+        }
+
+        output += &format!("*{}. Updated <t:{}:R>.*", self.footer(), self.updated_at / 1000);
+        output
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        let description = match self.entries.is_empty() {
+            true => String::from("Nobody has solved anything yet."),
+            false => standings_lines(&self.entries).join("\n"),
+        };
+
+        CreateEmbed::new()
+            .title(self.period.title())
+            .description(format!("{description}\n\nUpdated <t:{}:R>", self.updated_at / 1000))
+            .color(LEETCODE_COLOR)
+            .footer(CreateEmbedFooter::new(self.footer()))
+    }
+}
+
+/// How long a season lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeasonLength {
+    Week,
+    Month,
+    Quarter,
+}
+
+impl SeasonLength {
+    pub const ALL: [SeasonLength; 3] = [SeasonLength::Week, SeasonLength::Month, SeasonLength::Quarter];
+
+    /// The length's name, as given to `seasons`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeasonLength::Week => "week",
+            SeasonLength::Month => "month",
+            SeasonLength::Quarter => "quarter",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|length| length.name() == name)
+    }
+
+    /// When (in milliseconds) a season that started at `start` ends.
+    /// Months are calendar months: a monthly season starting on the 18th ends on the 18th.
+    pub fn end(&self, start: usize) -> usize {
+        let start = DateTime::from_timestamp_millis(start as i64).unwrap_or_default();
+        let end = match self {
+            SeasonLength::Week => start.checked_add_signed(TimeDelta::weeks(1)),
+            SeasonLength::Month => start.checked_add_months(Months::new(1)),
+            SeasonLength::Quarter => start.checked_add_months(Months::new(3)),
+        };
+
+        end.unwrap_or(start).timestamp_millis().max(0) as usize
+    }
+}
+
+/// A season's standings: final once it's over, or so far if it's the current one.
+#[derive(Debug, Clone)]
+pub struct Season {
+    pub number: u64,
+    /// When (in milliseconds) the season started, and ended (or will end).
+    pub started_at: usize,
+    pub ended_at: usize,
+    /// Best first.
+    pub standings: Vec<LeaderboardEntry>,
+    /// Whether the season is over.
+    pub is_over: bool,
+}
+
+impl Season {
+    fn title(&self) -> String {
+        match self.is_over {
+            true => format!("Season {} final standings", self.number),
+            false => format!("Season {} standings", self.number),
+        }
+    }
+
+    /// e.g. "Ended <t:...:R>" (as a Discord timestamp)
+    fn timespan(&self) -> String {
+        let verb = if self.is_over { "Ended" } else { "Ends" };
+        format!("Started <t:{}:D>. {verb} <t:{}:R>.", self.started_at / 1000, self.ended_at / 1000)
+    }
+}
+
+impl Render for Season {
+    fn render_text(&self) -> String {
+        let mut output = format!("**{}**\n{}\n", self.title(), self.timespan());
+        if self.standings.is_empty() {
+            output += "Nobody has solved anything.\n";
+        }
+        for line in standings_lines(&self.standings) {
+            output += &format!("{line}\n");
+        }
+
+        output
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        let standings = match self.standings.is_empty() {
+            true => String::from("Nobody has solved anything."),
+            false => standings_lines(&self.standings).join("\n"),
+        };
+
+        CreateEmbed::new()
+            .title(self.title())
+            .description(format!("{}\n\n{standings}", self.timespan()))
+            .color(LEETCODE_COLOR)
+    }
+}
+
+/// Two users, side by side.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub left: ComparedUser,
+    pub right: ComparedUser,
+
+    /// Titles of the problems both have solved, and of those only one of them has.
+    /// Only submissions we've seen count.
+    pub both_solved: Vec<String>,
+    pub only_left_solved: Vec<String>,
+    pub only_right_solved: Vec<String>,
+}
+
+/// One side of a `Comparison`.
+#[derive(Debug, Clone)]
+pub struct ComparedUser {
+    pub user: User,
+    /// Submissions made, and distinct problems solved, over the last 7 days.
+    pub week_submissions: usize,
+    pub week_solved: usize,
+}
+
+// Comparisons list a few of the problems in each group, and count the rest.
+const MAX_COMPARED_PROBLEMS: usize = 5;
+
+impl ComparedUser {
+    fn summary(&self) -> String {
+        format!(
+            "Easy: {}\nMedium: {}\nHard: {}\nTotal: {}\nRanking: {}\nStreak: {}\n\
+             This week: {} solved, {} submissions",
+            self.user.easy_solved,
+            self.user.medium_solved,
+            self.user.hard_solved,
+            self.user.total_solved,
+            self.user.ranking,
+            self.user.streak,
+            self.week_solved,
+            self.week_submissions
+        )
+    }
+}
+
+impl Comparison {
+    fn title(&self) -> String {
+        format!("{} vs {}", self.left.user.username, self.right.user.username)
+    }
+
+    /// (name, list) of each group of problems.
+    fn problem_groups(&self) -> [(String, String); 3] {
+        let list = |titles: &[String]| {
+            if titles.is_empty() {
+                return String::from("None");
+            }
+
+            let mut list = titles.iter().take(MAX_COMPARED_PROBLEMS).join(", ");
+            if titles.len() > MAX_COMPARED_PROBLEMS {
+                list += &format!(", and {} more", titles.len() - MAX_COMPARED_PROBLEMS);
+            }
+            list
+        };
+
+        [
+            (format!("Both solved ({})", self.both_solved.len()), list(&self.both_solved)),
+            (format!("Only {} solved ({})", self.left.user.username, self.only_left_solved.len()),
+             list(&self.only_left_solved)),
+            (format!("Only {} solved ({})", self.right.user.username, self.only_right_solved.len()),
+             list(&self.only_right_solved)),
+        ]
+    }
+}
+
+impl Render for Comparison {
+    fn render_text(&self) -> String {
+        let mut output = format!("**{}**\n", self.title());
+        for side in [&self.left, &self.right] {
+            output += &format!("**{}**\n\t{}\n",
+                               side.user.username, side.summary().replace('\n', "\n\t"));
+        }
+        for (name, list) in self.problem_groups() {
+            output += &format!("**{name}:** {list}\n");
+        }
+
+        output
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
+            .title(self.title())
+            .color(LEETCODE_COLOR)
+            .field(&self.left.user.username, self.left.summary(), true)
+            .field(&self.right.user.username, self.right.summary(), true);
+
+        for (name, list) in self.problem_groups() {
+            embed = embed.field(name, list, false);
+        }
+
+        embed.footer(CreateEmbedFooter::new("Solved problems only count submissions I've seen."))
+    }
+}
+
+/// A member daring another to solve a problem before a deadline.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    pub id: u64,
+    pub guild_id: u64,
+
+    /// LeetCode usernames of whoever issued the challenge, and of whoever has to solve it.
+    pub challenger: String,
+    pub challenged: String,
+    /// The title of the problem to solve.
+    pub problem: String,
+
+    /// When (in milliseconds) it was issued, and by when it has to be solved.
+    pub issued_at: usize,
+    pub deadline: usize,
+
+    pub outcome: ChallengeOutcome,
+    /// When (in milliseconds) it was won or expired, if it has been.
+    pub resolved_at: Option<usize>,
+}
+
+impl Challenge {
+    /// Whether solving it at `timestamp` (in milliseconds) wins it.
+    pub fn is_won_at(&self, timestamp: usize) -> bool {
+        (self.issued_at..=self.deadline).contains(&timestamp)
+    }
+}
+
+/// How a challenge turned out. The challenged user wins if they solve the problem in time;
+/// otherwise, the challenger does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeOutcome {
+    Open,
+    Won,
+    Expired,
+}
+
+impl ChallengeOutcome {
+    pub const ALL: [ChallengeOutcome; 3] =
+        [ChallengeOutcome::Open, ChallengeOutcome::Won, ChallengeOutcome::Expired];
+
+    /// The outcome's name, as stored.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChallengeOutcome::Open => "open",
+            ChallengeOutcome::Won => "won",
+            ChallengeOutcome::Expired => "expired",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|outcome| outcome.name() == name)
+    }
+}
+
+/// How many challenges a user has won and lost in a guild, on either side of them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChallengeRecord {
+    pub wins: usize,
+    pub losses: usize,
+}
+
+impl std::fmt::Display for ChallengeRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}W-{}L", self.wins, self.losses)
+    }
+}
+
+/// A message announcing `username`'s submission(s) in `guild_id`, which members can react to.
+#[derive(Debug, Clone)]
+pub struct SubmissionAnnouncement {
+    pub message_id: u64,
+    pub guild_id: u64,
+    pub username: String,
+}
+
+/// A member's reaction to a submission announcement. Each member gives its submitter at most
+/// one kudos per announcement, however many emojis they react with.
+#[derive(Debug, Clone)]
+pub struct Kudos {
+    pub message_id: u64,
+    pub giver_id: u64,
+    pub emoji: String,
+    /// When (in milliseconds) the reaction was added.
+    pub given_at: usize,
+}
+
+/// Milestones of a group goal (in percent), announced as they're reached.
+pub const GOAL_MILESTONES: [u64; 4] = [25, 50, 75, 100];
+
+// Progress bars are 20 blocks long, so each is 5%.
+const PROGRESS_BAR_LENGTH: u64 = 20;
+
+// Group goals show their top 5 contributors.
+const MAX_GOAL_CONTRIBUTORS: usize = 5;
+
+/// Midnight (UTC) of the Monday starting the week `now` is in.
+pub fn week_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let monday = now.date_naive() - TimeDelta::days(now.weekday().num_days_from_monday() as i64);
+    monday.and_time(Default::default()).and_utc()
+}
+
+/// How a guild's weekly group goal is coming along.
+#[derive(Debug, Clone)]
+pub struct GroupGoal {
+    /// How many solves are needed, and of which difficulty (any, if None).
+    pub target: u64,
+    pub difficulty: Option<String>,
+    pub progress: u64,
+    /// (username, solves) of everyone who contributed, most first.
+    pub contributors: Vec<(String, u64)>,
+    /// When (in milliseconds) the week started, and when it ends.
+    pub since: usize,
+    pub until: usize,
+}
+
+impl GroupGoal {
+    /// How far along the goal is, in percent. Can go past 100.
+    pub fn percent(&self) -> u64 {
+        self.progress * 100 / self.target.max(1)
+    }
+
+    /// The highest milestone reached, if any.
+    pub fn milestone(&self) -> Option<u64> {
+        GOAL_MILESTONES.into_iter().rev().find(|milestone| self.percent() >= *milestone)
+    }
+
+    /// e.g. "Weekly goal: 200 medium solves"
+    pub fn title(&self) -> String {
+        match &self.difficulty {
+            Some(difficulty) => format!("Weekly goal: {} {} solves",
+                                        self.target, difficulty.to_lowercase()),
+            None => format!("Weekly goal: {} solves", self.target),
+        }
+    }
+
+    /// e.g. "`▓▓▓▓▓░░░░░░░░░░░░░░░` 50/200 (25%)"
+    fn progress_bar(&self) -> String {
+        let filled = (self.percent() * PROGRESS_BAR_LENGTH / 100).min(PROGRESS_BAR_LENGTH);
+        let bar = "▓".repeat(filled as usize) + &"░".repeat((PROGRESS_BAR_LENGTH - filled) as usize);
+        format!("`{bar}` {}/{} ({}%)", self.progress, self.target, self.percent())
+    }
+
+    fn contributor_lines(&self) -> Vec<String> {
+        self.contributors
+            .iter()
+            .take(MAX_GOAL_CONTRIBUTORS)
+            .map(|(username, solves)| format!("**{username}**: {solves}"))
+            .collect()
+    }
+}
+
+impl Render for GroupGoal {
+    fn render_text(&self) -> String {
+        let mut output = format!("**{}**\n{}\nEnds <t:{}:R>.\n",
+                                 self.title(), self.progress_bar(), self.until / 1000);
+        for line in self.contributor_lines() {
+            output += &format!("\t{line}\n");
+        }
+
+        output
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new()
+            .title(self.title())
+            .description(format!("{}\n\nEnds <t:{}:R>", self.progress_bar(), self.until / 1000))
+            .color(LEETCODE_COLOR);
+
+        if !self.contributors.is_empty() {
+            embed = embed.field("Top contributors", self.contributor_lines().join("\n"), false);
+        }
+
+        embed
+    }
+}
+
+/// A curated list of problems to work through, e.g. Blind 75.
+#[derive(Debug, Clone)]
+pub struct ProblemList {
+    /// What it's called in commands, e.g. `blind75`.
+    pub name: String,
+    /// The titles of its problems, in order.
+    pub problems: Vec<String>,
+}
+
+/// How far along a problem list members are.
+#[derive(Debug, Clone)]
+pub struct ListProgress {
+    pub list: String,
+    pub total: usize,
+    /// (username, problems solved) of each member, most first.
+    pub members: Vec<(String, usize)>,
+    /// The problems left to solve, if this is about a single member.
+    pub remaining: Option<Vec<String>>,
+}
+
+// List progress shows at most 20 members, and names 10 of the problems left.
+const MAX_LIST_MEMBERS: usize = 20;
+const MAX_LIST_REMAINING: usize = 10;
+
+impl ListProgress {
+    fn member_lines(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .take(MAX_LIST_MEMBERS)
+            .map(|(username, solved)| format!("**{username}**: {solved}/{} ({}%)",
+                                              self.total, solved * 100 / self.total.max(1)))
+            .collect()
+    }
+
+    /// (name, list) of the problems left, if this is about a single member.
+    fn remaining_field(&self) -> Option<(String, String)> {
+        let remaining = self.remaining.as_ref()?;
+        if remaining.is_empty() {
+            return Some((String::from("Left to solve"), String::from("Nothing, it's all done!")));
+        }
+
+        let mut list = remaining.iter().take(MAX_LIST_REMAINING).join(", ");
+        if remaining.len() > MAX_LIST_REMAINING {
+            list += &format!(", and {} more", remaining.len() - MAX_LIST_REMAINING);
+        }
+        Some((format!("Left to solve ({})", remaining.len()), list))
+    }
+}
+
+impl Render for ListProgress {
+    fn render_text(&self) -> String {
+        let mut output = format!("**{} progress**\n", self.list);
+        if self.members.is_empty() {
+            output += "Nobody tracked here has solved any of it yet.\n";
+        }
+        for line in self.member_lines() {
+            output += &format!("\t{line}\n");
+        }
+        if let Some((name, list)) = self.remaining_field() {
+            output += &format!("**{name}:** {list}\n");
+        }
+
+        output
+    }
+
+    fn render_embed(&self) -> CreateEmbed {
+        let description = match self.members.is_empty() {
+            true => String::from("Nobody tracked here has solved any of it yet."),
+            false => self.member_lines().join("\n"),
+        };
+
+        let mut embed = CreateEmbed::new()
+            .title(format!("{} progress", self.list))
+            .description(description)
+            .color(LEETCODE_COLOR)
+            .footer(CreateEmbedFooter::new(format!("{} problems. Solves only count submissions \
+                                                    I've seen.", self.total)));
+
+        if let Some((name, list)) = self.remaining_field() {
+            embed = embed.field(name, list, false);
+        }
+
+        embed
+    }
+}